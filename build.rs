@@ -0,0 +1,38 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::env;
+use std::process::Command;
+
+/// Сведения о сборке для `/version`: коммит git и время сборки.
+fn main() {
+    // коммит из окружения (сборка без .git, например в Docker) или из репозитория
+    let commit: String = env::var("GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|commit| commit.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    // время сборки, SOURCE_DATE_EPOCH - для воспроизводимой сборки
+    let build_time: DateTime<Utc> = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .unwrap_or_else(Utc::now);
+
+    println!("cargo:rustc-env=GIT_COMMIT={commit}");
+    println!(
+        "cargo:rustc-env=BUILD_TIME={}",
+        build_time.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
@@ -1,3 +1,6 @@
+use crate::adapter::metrics::Metrics;
+use crate::config::{CatalogConfig, HttpConfig};
+use crate::domain::catalog::Catalog;
 use crate::domain::mortgage::Mortgage;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
@@ -5,19 +8,63 @@ use std::sync::{Arc, RwLock};
 /// State объект.
 pub type SharedState = Arc<RwLock<AppState>>;
 
-/// Кэш.
-#[derive(Default, Debug)]
+// Максимальный размер пакета расчетов по умолчанию.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Кэш, каталог программ и его источники, лимиты, состояние и метрики сервиса.
+#[derive(Debug)]
 pub struct AppState {
     pub id: u32,
     pub cache: HashMap<u32, Mortgage>,
+    // активный каталог: расчет берет снимок, перезагрузка атомарно заменяет указатель
+    pub catalog: Arc<Catalog>,
+    // файлы каталога и календаря для перезагрузки
+    pub catalog_source: CatalogConfig,
+    // токен административных эндпоинтов
+    pub admin_token: String,
+    // максимальный размер пакета расчетов
+    pub max_batch_size: usize,
+    // максимальное количество расчетов в кэше
+    pub max_cache_entries: Option<usize>,
+    // таймаут, лимит тела запроса и CORS
+    pub http: HttpConfig,
+    // сервис завершает работу и не принимает новый трафик
+    pub shutting_down: bool,
+    // метрики Prometheus
+    pub metrics: Arc<Metrics>,
 }
 
-/// Добавление в кэш.
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            id: u32::default(),
+            cache: HashMap::default(),
+            catalog: Arc::default(),
+            catalog_source: CatalogConfig::default(),
+            admin_token: String::new(),
+            max_batch_size: MAX_BATCH_SIZE,
+            max_cache_entries: None,
+            http: HttpConfig::default(),
+            shutting_down: false,
+            metrics: Arc::default(),
+        }
+    }
+}
+
+/// Добавление в кэш. При превышении лимита вытесняются самые старые расчеты:
+/// id выдаются подряд и удаляются только вытеснением, поэтому самый старый - `id - len`.
 pub async fn insert(state: Arc<RwLock<AppState>>, loan: Mortgage) -> u32 {
     let mut binding = state.write().unwrap();
     let id: u32 = binding.id;
     binding.cache.insert(id, loan);
     binding.id += 1;
+    if let Some(max_entries) = binding.max_cache_entries {
+        while binding.cache.len() > max_entries {
+            let oldest: u32 = binding.id - binding.cache.len() as u32;
+            binding.cache.remove(&oldest);
+            binding.metrics.eviction();
+        }
+    }
     id
 }
 
@@ -37,7 +84,9 @@ mod tests {
                 base: Some(true),
                 military: None,
                 salary: None,
+                family: None,
             },
+            ..Request::default()
         };
         let state: Arc<RwLock<AppState>> = SharedState::default();
         let loan: Mortgage = Mortgage::new(req);
@@ -45,4 +94,22 @@ mod tests {
         let actual: u32 = insert(state, loan).await;
         assert_eq!(actual, 0)
     }
+
+    #[tokio::test]
+    async fn test_insert_evicts() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        state.write().unwrap().max_cache_entries = Some(2);
+        for _ in 0..5 {
+            insert(Arc::clone(&state), Mortgage::default()).await;
+        }
+        // Ok. В кэше остались 2 последних расчета
+        let binding = state.read().unwrap();
+        let mut ids: Vec<u32> = binding.cache.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, vec![3, 4]);
+        assert!(binding
+            .metrics
+            .render(binding.cache.len())
+            .contains("mortgage_cache_evictions_total 3"));
+    }
 }
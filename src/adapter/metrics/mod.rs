@@ -0,0 +1,299 @@
+use crate::domain::mortgage::ProgramKind;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Тип содержимого текстового формата Prometheus.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+// Верхние границы корзин гистограммы длительности запросов, сек.
+const BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1_f64, 5_f64,
+];
+
+/// Метка запроса: маршрут, метод и http-код ответа.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RequestLabels {
+    pub route: String,
+    pub method: String,
+    pub status: u16,
+}
+
+/// Гистограмма длительности запросов.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Histogram {
+    // количество наблюдений в каждой корзине (не накопительное)
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+/// Метрики сервиса в формате Prometheus.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<RequestLabels, Histogram>>,
+    // успешные расчеты по программам
+    calculations: Mutex<BTreeMap<ProgramKind, u64>>,
+    // ошибки по вариантам AppError
+    errors: Mutex<BTreeMap<String, u64>>,
+    // вытеснения из кэша
+    cache_evictions: AtomicU64,
+    // перезагрузки каталога: успешные и неудачные
+    catalog_reloads: [AtomicU64; 2],
+}
+
+impl Histogram {
+    /// Учет наблюдения.
+    fn observe(&mut self, seconds: f64) {
+        if let Some(i) = BUCKETS.iter().position(|le| seconds <= *le) {
+            self.buckets[i] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+impl Metrics {
+    /// Учет обработанного запроса.
+    pub fn request(&self, labels: RequestLabels, duration: Duration) {
+        self.requests
+            .lock()
+            .unwrap()
+            .entry(labels)
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Учет успешного расчета по программе.
+    pub fn calculation(&self, program: Option<ProgramKind>) {
+        if let Some(program) = program {
+            *self
+                .calculations
+                .lock()
+                .unwrap()
+                .entry(program)
+                .or_default() += 1;
+        }
+    }
+
+    /// Учет ошибки по варианту AppError.
+    pub fn error(&self, kind: &str) {
+        *self
+            .errors
+            .lock()
+            .unwrap()
+            .entry(kind.to_string())
+            .or_default() += 1;
+    }
+
+    /// Учет вытеснения расчета из кэша.
+    pub fn eviction(&self) {
+        self.cache_evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Учет перезагрузки каталога программ.
+    pub fn catalog_reload(&self, success: bool) {
+        self.catalog_reloads[usize::from(!success)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Выгрузка метрик в текстовом формате Prometheus.
+    pub fn render(&self, cache_entries: usize) -> String {
+        let mut out: String = String::new();
+
+        let requests = self.requests.lock().unwrap();
+        header(
+            &mut out,
+            "http_requests_total",
+            "counter",
+            "Total HTTP requests.",
+        );
+        for (labels, histogram) in requests.iter() {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{{}}} {}",
+                labels.render(),
+                histogram.count
+            );
+        }
+        header(
+            &mut out,
+            "http_request_duration_seconds",
+            "histogram",
+            "HTTP request latency in seconds.",
+        );
+        for (labels, histogram) in requests.iter() {
+            let labels: String = labels.render();
+            let mut cumulative: u64 = 0;
+            for (le, count) in BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{{labels},le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_sum{{{labels}}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_count{{{labels}}} {}",
+                histogram.count
+            );
+        }
+        drop(requests);
+
+        header(
+            &mut out,
+            "mortgage_calculations_total",
+            "counter",
+            "Successful mortgage calculations by program.",
+        );
+        for (program, count) in self.calculations.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "mortgage_calculations_total{{program=\"{}\"}} {count}",
+                program.as_str()
+            );
+        }
+        header(
+            &mut out,
+            "mortgage_errors_total",
+            "counter",
+            "Validation and calculation failures by error.",
+        );
+        for (kind, count) in self.errors.lock().unwrap().iter() {
+            let _ = writeln!(out, "mortgage_errors_total{{error=\"{kind}\"}} {count}");
+        }
+        header(
+            &mut out,
+            "mortgage_cache_entries",
+            "gauge",
+            "Loans stored in the cache.",
+        );
+        let _ = writeln!(out, "mortgage_cache_entries {cache_entries}");
+        header(
+            &mut out,
+            "mortgage_cache_evictions_total",
+            "counter",
+            "Loans evicted from the cache.",
+        );
+        let _ = writeln!(
+            out,
+            "mortgage_cache_evictions_total {}",
+            self.cache_evictions.load(Ordering::Relaxed)
+        );
+        header(
+            &mut out,
+            "mortgage_catalog_reloads_total",
+            "counter",
+            "Program catalog reloads by result.",
+        );
+        for (result, count) in ["success", "failure"].iter().zip(&self.catalog_reloads) {
+            let _ = writeln!(
+                out,
+                "mortgage_catalog_reloads_total{{result=\"{result}\"}} {}",
+                count.load(Ordering::Relaxed)
+            );
+        }
+        out
+    }
+}
+
+impl RequestLabels {
+    /// Метки в формате Prometheus.
+    fn render(&self) -> String {
+        format!(
+            "route=\"{}\",method=\"{}\",status=\"{}\"",
+            escape(&self.route),
+            self.method,
+            self.status
+        )
+    }
+}
+
+/// Описание и тип метрики.
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Экранирование значения метки.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(route: &str, status: u16) -> RequestLabels {
+        RequestLabels {
+            route: route.to_string(),
+            method: "POST".to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut histogram: Histogram = Histogram::default();
+        histogram.observe(0.0003);
+        histogram.observe(0.003);
+        histogram.observe(30_f64);
+        assert_eq!(histogram.buckets[0], 1);
+        assert_eq!(histogram.buckets[3], 1);
+        assert_eq!(histogram.buckets.iter().sum::<u64>(), 2);
+        assert_eq!(histogram.count, 3);
+    }
+
+    #[test]
+    fn test_render() {
+        let metrics: Metrics = Metrics::default();
+        metrics.request(labels("/v1/execute", 200), Duration::from_micros(300));
+        metrics.request(labels("/v1/execute", 200), Duration::from_millis(3));
+        metrics.request(labels("/v1/execute", 400), Duration::from_micros(100));
+        metrics.calculation(Some(ProgramKind::Base));
+        metrics.calculation(Some(ProgramKind::Base));
+        metrics.calculation(None);
+        metrics.error("loan_program_empty");
+
+        let actual: String = metrics.render(2);
+        let route: &str = "route=\"/v1/execute\",method=\"POST\"";
+        for line in [
+            format!("http_requests_total{{{route},status=\"200\"}} 2"),
+            format!("http_requests_total{{{route},status=\"400\"}} 1"),
+            format!(
+                "http_request_duration_seconds_bucket{{{route},status=\"200\",le=\"0.0005\"}} 1"
+            ),
+            format!(
+                "http_request_duration_seconds_bucket{{{route},status=\"200\",le=\"0.005\"}} 2"
+            ),
+            format!("http_request_duration_seconds_bucket{{{route},status=\"200\",le=\"+Inf\"}} 2"),
+            format!("http_request_duration_seconds_count{{{route},status=\"200\"}} 2"),
+            "# TYPE http_request_duration_seconds histogram".to_string(),
+            "mortgage_calculations_total{program=\"base\"} 2".to_string(),
+            "mortgage_errors_total{error=\"loan_program_empty\"} 1".to_string(),
+            "mortgage_cache_entries 2".to_string(),
+            "mortgage_cache_evictions_total 0".to_string(),
+            "mortgage_catalog_reloads_total{result=\"failure\"} 0".to_string(),
+        ] {
+            assert!(actual.lines().any(|l| l == line), "{line}\n{actual}");
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+}
@@ -1,2 +1,5 @@
 pub mod cache;
+pub mod metrics;
+pub mod reload;
 pub mod router;
+pub mod telemetry;
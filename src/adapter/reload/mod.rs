@@ -0,0 +1,287 @@
+use crate::adapter::cache::SharedState;
+use crate::config::CatalogConfig;
+use crate::domain::calendar::Calendar;
+use crate::domain::catalog::{Catalog, CatalogError, BUILTIN_VERSION};
+use crate::schema::CatalogReload;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Ошибки загрузки каталога программ.
+#[derive(Debug, Error)]
+pub enum LoadError {
+    // файл каталога не читается или некорректен
+    #[error("failed to load program catalog {path}: {source}")]
+    Catalog { path: PathBuf, source: io::Error },
+    // в каталоге из файла не задана версия
+    #[error("program catalog {path} must set `version`")]
+    Version { path: PathBuf },
+    // в каталоге нет программ
+    #[error("program catalog {path} has no programs")]
+    Empty { path: PathBuf },
+    // каталог из файла не прошел проверку правил программ
+    #[error("invalid program catalog {path}: {source}")]
+    Invalid { path: PathBuf, source: CatalogError },
+    // файл календаря не читается или некорректен
+    #[error("failed to load holidays {path}: {source}")]
+    Holidays { path: PathBuf, source: io::Error },
+}
+
+/// Загрузка каталога программ и производственного календаря из файлов конфигурации,
+/// без файла каталога - встроенный каталог.
+pub fn load(source: &CatalogConfig) -> Result<Catalog, LoadError> {
+    let mut catalog: Catalog = match &source.path {
+        Some(path) => {
+            let catalog: Catalog =
+                Catalog::from_file(path).map_err(|source| LoadError::Catalog {
+                    path: path.clone(),
+                    source,
+                })?;
+            // версия записывается в каждый расчет, по ней видно, по каким ставкам он сделан
+            if catalog.version.is_empty() || catalog.version == BUILTIN_VERSION {
+                return Err(LoadError::Version { path: path.clone() });
+            }
+            if catalog.programs.is_empty() {
+                return Err(LoadError::Empty { path: path.clone() });
+            }
+            catalog.validate().map_err(|source| LoadError::Invalid {
+                path: path.clone(),
+                source,
+            })?;
+            catalog
+        }
+        None => Catalog::default(),
+    };
+    if let Some(path) = &source.holidays {
+        let calendar: Calendar =
+            Calendar::from_file(path).map_err(|source| LoadError::Holidays {
+                path: path.clone(),
+                source,
+            })?;
+        catalog.calendar = Arc::new(calendar);
+    }
+    Ok(catalog)
+}
+
+/// Перезагрузка каталога из файлов и атомарная замена активного каталога.
+/// Файлы читаются без блокировки, расчеты в процессе завершаются по своему снимку каталога,
+/// новые - идут по новому. При ошибке активный каталог не меняется.
+pub fn reload(state: &SharedState, trigger: &str) -> Result<CatalogReload, LoadError> {
+    let (source, metrics) = {
+        let binding = state.read().unwrap();
+        (binding.catalog_source.clone(), Arc::clone(&binding.metrics))
+    };
+    let catalog: Catalog = match load(&source) {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            metrics.catalog_reload(false);
+            warn!(trigger, error = %err, "catalog reload failed");
+            return Err(err);
+        }
+    };
+    let catalog: Arc<Catalog> = Arc::new(catalog);
+    let previous: Arc<Catalog> =
+        std::mem::replace(&mut state.write().unwrap().catalog, Arc::clone(&catalog));
+    metrics.catalog_reload(true);
+    let resp: CatalogReload = CatalogReload {
+        previous_version: previous.version.clone(),
+        catalog_version: catalog.version.clone(),
+        programs: catalog.programs.len(),
+        holidays: catalog.calendar.holidays.len(),
+    };
+    info!(
+        trigger,
+        previous_version = %resp.previous_version,
+        catalog_version = %resp.catalog_version,
+        programs = resp.programs,
+        holidays = resp.holidays,
+        "catalog reloaded"
+    );
+    Ok(resp)
+}
+
+/// Перезагрузка каталога по сигналу SIGHUP.
+#[cfg(unix)]
+pub async fn on_hangup(state: SharedState) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            warn!(error = %err, "failed to install SIGHUP handler, catalog reload by signal is disabled");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        let state: SharedState = Arc::clone(&state);
+        // чтение файлов вне асинхронного рантайма
+        let _ = tokio::task::spawn_blocking(move || reload(&state, "sighup")).await;
+    }
+}
+
+/// Перезагрузка каталога при изменении файлов: время изменения проверяется с периодом `interval`.
+/// Неудачная перезагрузка (например, файл записан не полностью) повторяется при следующем изменении.
+pub async fn watch(state: SharedState, interval: Duration) {
+    let source: CatalogConfig = state.read().unwrap().catalog_source.clone();
+    let mut last: Vec<Option<SystemTime>> = modified(&source);
+    loop {
+        tokio::time::sleep(interval).await;
+        let current: Vec<Option<SystemTime>> = modified(&source);
+        if current != last {
+            last = current;
+            let state: SharedState = Arc::clone(&state);
+            // чтение файлов вне асинхронного рантайма
+            let _ = tokio::task::spawn_blocking(move || reload(&state, "file_change")).await;
+        }
+    }
+}
+
+/// Время изменения файлов каталога и календаря.
+fn modified(source: &CatalogConfig) -> Vec<Option<SystemTime>> {
+    [&source.path, &source.holidays]
+        .into_iter()
+        .flatten()
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::cache::AppState;
+    use crate::domain::mortgage::ProgramKind;
+    use std::fs;
+    use std::sync::RwLock;
+
+    /// Файлы каталога и календаря во временной директории.
+    fn source(name: &str) -> CatalogConfig {
+        let dir: PathBuf = std::env::temp_dir();
+        let id: u32 = std::process::id();
+        CatalogConfig {
+            path: Some(dir.join(format!("mg-{name}-{id}.json"))),
+            holidays: Some(dir.join(format!("mg-{name}-{id}.txt"))),
+            reload_interval_secs: 0,
+        }
+    }
+
+    /// Запись каталога с версией и ставкой базовой программы.
+    fn write(source: &CatalogConfig, version: &str, rate: f64) {
+        let mut catalog: Catalog = Catalog {
+            version: version.to_string(),
+            ..Catalog::default()
+        };
+        catalog.programs.get_mut(&ProgramKind::Base).unwrap().rate = rate;
+        fs::write(
+            source.path.as_ref().unwrap(),
+            serde_json::to_vec(&catalog).unwrap(),
+        )
+        .unwrap();
+        fs::write(source.holidays.as_ref().unwrap(), "2025-01-01\n").unwrap();
+    }
+
+    fn cleanup(source: &CatalogConfig) {
+        for path in [&source.path, &source.holidays].into_iter().flatten() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        // Ok. Встроенный каталог
+        let actual: Catalog = load(&CatalogConfig::default()).unwrap();
+        assert_eq!(actual.version, BUILTIN_VERSION);
+        // Ok. Каталог и календарь из файлов
+        let source: CatalogConfig = source("load");
+        write(&source, "2025-06", 12_f64);
+        let actual: Catalog = load(&source).unwrap();
+        assert_eq!(actual.version, "2025-06");
+        assert_eq!(actual.rules(ProgramKind::Base).unwrap().rate, 12_f64);
+        assert_eq!(actual.calendar.holidays.len(), 1);
+        // Err. Версия не задана
+        write(&source, BUILTIN_VERSION, 12_f64);
+        assert!(matches!(load(&source), Err(LoadError::Version { .. })));
+        // Err. Ставка не положительная
+        write(&source, "2025-06", 0_f64);
+        assert!(matches!(load(&source), Err(LoadError::Invalid { .. })));
+        // Err. Некорректный календарь
+        write(&source, "2025-06", 12_f64);
+        fs::write(source.holidays.as_ref().unwrap(), "2025-13-01").unwrap();
+        assert!(matches!(load(&source), Err(LoadError::Holidays { .. })));
+        cleanup(&source);
+        // Err. Нет файла
+        assert!(matches!(load(&source), Err(LoadError::Catalog { .. })));
+    }
+
+    #[test]
+    fn test_reload() {
+        let source: CatalogConfig = source("reload");
+        write(&source, "2025-06", 12_f64);
+        let state: SharedState = Arc::new(RwLock::new(AppState {
+            catalog_source: source.clone(),
+            ..AppState::default()
+        }));
+        // снимок каталога расчета, начатого до перезагрузки
+        let snapshot: Arc<Catalog> = Arc::clone(&state.read().unwrap().catalog);
+        // Ok. Каталог заменен
+        let actual: CatalogReload = reload(&state, "test").unwrap();
+        assert_eq!(actual.previous_version, BUILTIN_VERSION);
+        assert_eq!(actual.catalog_version, "2025-06");
+        assert_eq!(actual.holidays, 1);
+        assert_eq!(state.read().unwrap().catalog.version, "2025-06");
+        // Ok. Снимок не изменился
+        assert_eq!(snapshot.version, BUILTIN_VERSION);
+        // Err. Некорректный файл, активный каталог прежний
+        fs::write(source.path.as_ref().unwrap(), "{").unwrap();
+        assert!(reload(&state, "test").is_err());
+        assert_eq!(state.read().unwrap().catalog.version, "2025-06");
+        // Err. Каталог не прошел проверку, активный каталог прежний
+        write(&source, "2025-07", -1_f64);
+        assert!(matches!(
+            reload(&state, "test"),
+            Err(LoadError::Invalid { .. })
+        ));
+        assert_eq!(state.read().unwrap().catalog.version, "2025-06");
+        let metrics: String = state.read().unwrap().metrics.render(0);
+        assert!(metrics.contains("mortgage_catalog_reloads_total{result=\"success\"} 1"));
+        assert!(metrics.contains("mortgage_catalog_reloads_total{result=\"failure\"} 2"));
+        cleanup(&source);
+    }
+
+    #[tokio::test]
+    async fn test_watch() {
+        let source: CatalogConfig = source("watch");
+        write(&source, "2025-06", 12_f64);
+        let state: SharedState = Arc::new(RwLock::new(AppState {
+            catalog_source: source.clone(),
+            ..AppState::default()
+        }));
+        let task = tokio::spawn(watch(Arc::clone(&state), Duration::from_millis(10)));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        // Ok. Без изменений каталог не перезагружается
+        assert_eq!(state.read().unwrap().catalog.version, BUILTIN_VERSION);
+        // Ok. Изменение файла
+        let file = fs::File::options()
+            .write(true)
+            .open(source.path.as_ref().unwrap())
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let mut version: String = String::new();
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            version = state.read().unwrap().catalog.version.clone();
+            if version != BUILTIN_VERSION {
+                break;
+            }
+        }
+        assert_eq!(version, "2025-06");
+        task.abort();
+        cleanup(&source);
+    }
+}
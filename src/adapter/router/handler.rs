@@ -1,33 +1,59 @@
 use crate::adapter::cache::{insert, SharedState};
-use crate::domain::mortgage::Mortgage;
-use crate::errors::AppError::EmptyCache;
-use crate::errors::{AppError, Result};
-use crate::schema::{Request, Response};
-use axum::extract::State;
+use crate::adapter::metrics::{self, Metrics};
+use crate::adapter::reload::reload;
+use crate::adapter::router::ndjson;
+use crate::domain::catalog::Catalog;
+use crate::domain::grid::{grid_calc, Grid, GridParams};
+use crate::domain::mortgage::{Mortgage, Params, ProgramKind};
+use crate::domain::refinance::{refinance_calc, Refinance, RefinanceParams};
+use crate::domain::simulation::{simulation_calc, Simulation, SimulationParams};
+use crate::domain::stress::{stress_calc, Stress, StressParams};
+use crate::errors::AppError::{
+    BatchTooLarge, EmptyCache, LoanNotFound, ReloadFailed, Unauthorized,
+};
+use crate::errors::{AppError, ErrorResponse, Result};
+use crate::schema::{
+    v2, BatchItem, BatchQuery, BuildInfo, CatalogReload, Comparison, ComparisonItem, Format,
+    GridQuery, Health, Ineligible, Readiness, Request, Response, Status,
+};
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response as HttpResponse};
 use axum::Json;
+use futures_util::stream::{self, StreamExt};
+use rayon::prelude::*;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::future::ready;
+use std::sync::Arc;
+use tracing::{instrument, Span};
 
 /// Эндпоинт расчета ипотеки.
+#[utoipa::path(
+    post,
+    path = "/execute",
+    request_body = Request,
+    responses(
+        (status = 200, description = "Расчет кредита", body = Response),
+        (status = 400, description = "Некорректные параметры", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
 pub async fn mortgage(
     State(state): State<SharedState>,
     Json(req): Json<Request>,
 ) -> Result<Json<Response>, AppError> {
     // объект кредита с нужными полями
     let mut loan: Mortgage = Mortgage::new(req);
-    // проверка на наличие больше 1 программы в запросе
-    loan.loan_program_check()?;
-    // проверка минимальной суммы первоначального взноса
-    loan.min_initial_payment_check()?;
-    // расчет суммы кредита
-    loan.loan_sum_calc();
-    // Определение процентной ставки
-    loan.rate_calc();
-    // расчет ежемесячного платежа
-    loan.monthly_payment_calc();
-    // расчет переплаты
-    loan.overpayment_calc();
-    // расчет даты последнего платежа
-    loan.last_payment_date_calc();
+    // расчет кредита по снимку каталога программ
+    let catalog: Arc<Catalog> = Arc::clone(&state.read().unwrap().catalog);
+    loan.execute(&catalog)?;
+    state
+        .read()
+        .unwrap()
+        .metrics
+        .calculation(loan.program.kind());
     // запись расчета в кэш
     let id: u32 = insert(state, loan.clone()).await;
     // формирование ответа
@@ -36,7 +62,393 @@ pub async fn mortgage(
     Ok(Json(resp))
 }
 
+/// Эндпоинт пакетного расчета ипотек.
+/// Результаты возвращаются в порядке запросов, ошибка элемента не прерывает расчет пакета.
+#[utoipa::path(
+    post,
+    path = "/execute/batch",
+    params(BatchQuery),
+    request_body = Vec<Request>,
+    responses(
+        (status = 200, description = "Результаты расчетов", body = Vec<BatchItem>),
+        (status = 413, description = "Размер пакета больше допустимого", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn batch(
+    State(state): State<SharedState>,
+    Query(query): Query<BatchQuery>,
+    Json(items): Json<Vec<Value>>,
+) -> Result<Json<Vec<BatchItem>>, AppError> {
+    let (catalog, metrics, max_size): (Arc<Catalog>, Arc<Metrics>, usize) = {
+        let binding = state.read().unwrap();
+        (
+            Arc::clone(&binding.catalog),
+            Arc::clone(&binding.metrics),
+            binding.max_batch_size,
+        )
+    };
+    // проверка размера пакета
+    if items.len() > max_size {
+        return Err(BatchTooLarge { max_size });
+    }
+    // параллельный расчет вне асинхронного рантайма, спаны расчетов - в спане хендлера
+    let span: Span = Span::current();
+    let results: Vec<Result<Mortgage, String>> = tokio::task::spawn_blocking(move || {
+        items
+            .into_par_iter()
+            .map(|item| span.in_scope(|| execute(serde_json::from_value(item), &catalog, &metrics)))
+            .collect()
+    })
+    .await
+    .unwrap();
+    // формирование ответа и запись в кэш
+    let mut resp: Vec<BatchItem> = Vec::with_capacity(results.len());
+    for (index, result) in results.into_iter().enumerate() {
+        resp.push(batch_item(Arc::clone(&state), index, result, query.cache).await);
+    }
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Эндпоинт потокового расчета ипотек: запросы и результаты в формате NDJSON.
+/// Результат каждой строки отправляется сразу после расчета, тело запроса не буферизуется.
+#[utoipa::path(
+    post,
+    path = "/execute/stream",
+    params(BatchQuery),
+    request_body(content = Request, content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "Результаты расчетов по строкам", body = BatchItem,
+            content_type = "application/x-ndjson"),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn mortgage_stream(
+    State(state): State<SharedState>,
+    Query(query): Query<BatchQuery>,
+    body: Body,
+) -> HttpResponse {
+    let (catalog, metrics, max_line): (Arc<Catalog>, Arc<Metrics>, usize) = {
+        let binding = state.read().unwrap();
+        (
+            Arc::clone(&binding.catalog),
+            Arc::clone(&binding.metrics),
+            binding.http.body_limit_bytes,
+        )
+    };
+    // расчет строк по мере поступления вне асинхронного рантайма,
+    // спан хендлера открыт до конца потока; строка больше лимита тела запроса завершает поток
+    let span: Span = Span::current();
+    let items = ndjson::lines(body, max_line)
+        .enumerate()
+        .then(move |(index, line)| {
+            let (state, catalog, metrics, span) = (
+                Arc::clone(&state),
+                Arc::clone(&catalog),
+                Arc::clone(&metrics),
+                span.clone(),
+            );
+            async move {
+                let result: Result<Mortgage, String> = match line {
+                    Ok(line) => tokio::task::spawn_blocking(move || {
+                        span.in_scope(|| execute(serde_json::from_slice(&line), &catalog, &metrics))
+                    })
+                    .await
+                    .unwrap(),
+                    Err(err) => {
+                        metrics.error(err.kind());
+                        Err(err.to_string())
+                    }
+                };
+                batch_item(state, index, result, query.cache).await
+            }
+        });
+    // ответ 200
+    ndjson::response(items)
+}
+
+/// Расчет элемента пакета, ошибки разбора и расчета возвращаются текстом.
+fn execute(
+    req: serde_json::Result<Request>,
+    catalog: &Catalog,
+    metrics: &Metrics,
+) -> Result<Mortgage, String> {
+    let mut loan: Mortgage = Mortgage::new(req.map_err(|err| err.to_string())?);
+    match loan.execute(catalog) {
+        Ok(()) => {
+            metrics.calculation(loan.program.kind());
+            Ok(loan)
+        }
+        Err(err) => {
+            metrics.error(err.kind());
+            Err(err.to_string())
+        }
+    }
+}
+
+/// Результат элемента пакета, успешный расчет при необходимости сохраняется в кэш.
+async fn batch_item(
+    state: SharedState,
+    index: usize,
+    result: Result<Mortgage, String>,
+    cache: bool,
+) -> BatchItem {
+    match result {
+        Ok(loan) => BatchItem {
+            index,
+            id: if cache {
+                Some(insert(state, loan.clone()).await)
+            } else {
+                None
+            },
+            loan: Some(loan),
+            error: None,
+        },
+        Err(error) => BatchItem {
+            index,
+            id: None,
+            loan: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Эндпоинт расчета ипотеки v2.
+#[utoipa::path(
+    post,
+    path = "/execute",
+    request_body = v2::Request,
+    responses(
+        (status = 200, description = "Расчет кредита", body = v2::Response),
+        (status = 400, description = "Некорректные параметры", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn mortgage_v2(
+    State(state): State<SharedState>,
+    Json(req): Json<v2::Request>,
+) -> Result<Json<v2::Response>, AppError> {
+    // объект кредита с нужными полями
+    let mut loan: Mortgage = Mortgage::from(req);
+    // расчет кредита по снимку каталога программ
+    let catalog: Arc<Catalog> = Arc::clone(&state.read().unwrap().catalog);
+    loan.execute(&catalog)?;
+    state
+        .read()
+        .unwrap()
+        .metrics
+        .calculation(loan.program.kind());
+    // запись расчета в кэш
+    let id: u32 = insert(state, loan.clone()).await;
+    // ответ 200
+    Ok(Json(v2::Response::new(id, loan)))
+}
+
+/// Эндпоинт сравнения программ кредитования.
+#[utoipa::path(
+    post,
+    path = "/compare",
+    request_body = Params,
+    responses(
+        (status = 200, description = "Сравнение программ", body = Comparison),
+        (status = 400, description = "Некорректный срок кредита", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn compare(
+    State(state): State<SharedState>,
+    Json(params): Json<Params>,
+) -> Result<Json<Comparison>, AppError> {
+    // проверка срока до расчета по программам
+    params.check()?;
+    let catalog: Arc<Catalog> = Arc::clone(&state.read().unwrap().catalog);
+    let mut resp: Comparison = Comparison::default();
+    // расчет по каждой программе
+    for program in ProgramKind::ALL {
+        let mut loan: Mortgage = Mortgage {
+            params: params.clone(),
+            program: program.into(),
+            ..Mortgage::default()
+        };
+        match loan.execute(&catalog) {
+            Ok(()) => resp.eligible.push(ComparisonItem {
+                program,
+                loan,
+                monthly_payment_diff: f64::default(),
+                total_cost_diff: f64::default(),
+            }),
+            Err(err) => resp.ineligible.push(Ineligible {
+                program,
+                error: err.to_string(),
+            }),
+        }
+    }
+    // сортировка по переплате
+    resp.eligible.sort_by(|a, b| {
+        a.loan
+            .aggregates
+            .overpayment
+            .total_cmp(&b.loan.aggregates.overpayment)
+    });
+    // разница с лучшей программой
+    if let Some(best) = resp.eligible.first().map(|item| item.loan.clone()) {
+        for item in resp.eligible.iter_mut() {
+            item.monthly_payment_diff =
+                item.loan.aggregates.monthly_payment - best.aggregates.monthly_payment;
+            item.total_cost_diff = item.loan.total_cost() - best.total_cost();
+        }
+    }
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Эндпоинт сетки платежей по ставкам и срокам.
+#[utoipa::path(
+    post,
+    path = "/grid",
+    params(GridQuery),
+    request_body = GridParams,
+    responses(
+        (status = 200, description = "Сетка платежей", content(
+            (Grid = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 400, description = "Некорректные параметры", body = ErrorResponse),
+    )
+)]
+pub async fn grid(
+    Query(query): Query<GridQuery>,
+    Json(params): Json<GridParams>,
+) -> Result<HttpResponse, AppError> {
+    // расчет сетки
+    let grid: Grid = grid_calc(&params)?;
+    // ответ 200 в запрошенном формате
+    match query.format {
+        Format::Json => Ok(Json(grid).into_response()),
+        Format::Csv => Ok((
+            [(header::CONTENT_TYPE, mime::TEXT_CSV_UTF_8.as_ref())],
+            grid.to_csv(),
+        )
+            .into_response()),
+    }
+}
+
+/// Эндпоинт расчета рефинансирования.
+#[utoipa::path(
+    post,
+    path = "/refinance",
+    request_body = RefinanceParams,
+    responses(
+        (status = 200, description = "Расчет рефинансирования", body = Refinance),
+        (status = 400, description = "Некорректные параметры", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn refinance(
+    State(state): State<SharedState>,
+    Json(params): Json<RefinanceParams>,
+) -> Result<Json<Refinance>, AppError> {
+    // расчет действующего и нового кредитов
+    let catalog: Arc<Catalog> = Arc::clone(&state.read().unwrap().catalog);
+    let resp: Refinance = refinance_calc(params, &catalog)?;
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Эндпоинт стресс-теста рассчитанного кредита.
+#[utoipa::path(
+    post,
+    path = "/stress",
+    request_body = StressParams,
+    responses(
+        (status = 200, description = "Результаты сценариев", body = Stress),
+        (status = 400, description = "Некорректные сценарии", body = ErrorResponse),
+        (status = 404, description = "Расчет не найден в кэше", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn stress(
+    State(state): State<SharedState>,
+    Json(params): Json<StressParams>,
+) -> Result<Json<Stress>, AppError> {
+    let binding = state.read().unwrap();
+    // получение расчета из кэша
+    let loan: &Mortgage = binding.cache.get(&params.id).ok_or(LoanNotFound)?;
+    // расчет сценариев
+    let resp: Stress = stress_calc(loan, &params, binding.catalog.max_payment_to_income)?;
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Эндпоинт симуляции плавающей ставки рассчитанного кредита.
+#[utoipa::path(
+    post,
+    path = "/simulate",
+    request_body = SimulationParams,
+    responses(
+        (status = 200, description = "Результат симуляции", body = Simulation),
+        (status = 400, description = "Некорректные параметры", body = ErrorResponse),
+        (status = 404, description = "Расчет не найден в кэше", body = ErrorResponse),
+    )
+)]
+#[instrument(skip_all)]
+pub async fn simulate(
+    State(state): State<SharedState>,
+    Json(params): Json<SimulationParams>,
+) -> Result<Json<Simulation>, AppError> {
+    // получение расчета из кэша
+    let (loan, max_payment_to_income): (Mortgage, f64) = {
+        let binding = state.read().unwrap();
+        let loan: &Mortgage = binding.cache.get(&params.id).ok_or(LoanNotFound)?;
+        (loan.clone(), binding.catalog.max_payment_to_income)
+    };
+    // симуляция вне асинхронного рантайма
+    let resp: Simulation =
+        tokio::task::spawn_blocking(move || simulation_calc(&loan, &params, max_payment_to_income))
+            .await
+            .unwrap()?;
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Получение из кэша всех расчитанных ипотек v2.
+#[utoipa::path(
+    get,
+    path = "/cache",
+    responses(
+        (status = 200, description = "Рассчитанные кредиты", body = Vec<v2::Response>),
+        (status = 400, description = "Кэш пуст", body = ErrorResponse),
+    )
+)]
+pub async fn cache_v2(
+    State(state): State<SharedState>,
+) -> Result<Json<Vec<v2::Response>>, AppError> {
+    // получение кэша
+    let cache: &HashMap<u32, Mortgage> = &state.read().unwrap().cache;
+    // проверка на пустой кэш
+    if cache.is_empty() {
+        return Err(EmptyCache);
+    }
+    // перекладка из кэша в порядке расчета
+    let mut resp: Vec<v2::Response> = cache
+        .iter()
+        .map(|(id, loan)| v2::Response::new(*id, loan.clone()))
+        .collect();
+    resp.sort_by_key(|r| r.id);
+    // ответ 200
+    Ok(Json(resp))
+}
+
 /// Получение из кэша всех расчитанных ипотек.
+#[utoipa::path(
+    get,
+    path = "/cache",
+    responses(
+        (status = 200, description = "Рассчитанные кредиты", body = Vec<Response>),
+        (status = 400, description = "Кэш пуст", body = ErrorResponse),
+    )
+)]
 pub async fn cache(State(state): State<SharedState>) -> Result<Json<Vec<Response>>, AppError> {
     // получение кэша
     let cache: &HashMap<u32, Mortgage> = &state.read().unwrap().cache;
@@ -57,12 +469,158 @@ pub async fn cache(State(state): State<SharedState>) -> Result<Json<Vec<Response
     Ok(Json(resp))
 }
 
+/// Потоковая выгрузка кэша в формате NDJSON в порядке расчета.
+#[utoipa::path(
+    get,
+    path = "/cache/stream",
+    responses(
+        (status = 200, description = "Рассчитанные кредиты по строкам", body = Response,
+            content_type = "application/x-ndjson"),
+        (status = 400, description = "Кэш пуст", body = ErrorResponse),
+    )
+)]
+pub async fn cache_stream(State(state): State<SharedState>) -> Result<HttpResponse, AppError> {
+    // id расчетов в порядке расчета
+    let mut ids: Vec<u32> = state.read().unwrap().cache.keys().copied().collect();
+    // проверка на пустой кэш
+    if ids.is_empty() {
+        return Err(EmptyCache);
+    }
+    ids.sort_unstable();
+    // кредиты читаются из кэша по одному при отправке
+    let items = stream::iter(ids).filter_map(move |id| {
+        let loan: Option<Mortgage> = state.read().unwrap().cache.get(&id).cloned();
+        ready(loan.map(|loan| Response::new(id, loan)))
+    });
+    // ответ 200
+    Ok(ndjson::response(items))
+}
+
+/// Проверка живости: процесс отвечает на запросы.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    responses((status = 200, description = "Сервис жив", body = Health))
+)]
+pub async fn healthz() -> Json<Health> {
+    Json(Health { status: Status::Ok })
+}
+
+/// Проверка готовности: каталог программ загружен и сервис не завершает работу.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Сервис готов принимать трафик", body = Readiness),
+        (status = 503, description = "Сервис не готов", body = Readiness),
+    )
+)]
+pub async fn readyz(State(state): State<SharedState>) -> (StatusCode, Json<Readiness>) {
+    let binding = state.read().unwrap();
+    let ready: bool = !binding.shutting_down && !binding.catalog.programs.is_empty();
+    let resp: Readiness = Readiness {
+        status: if ready { Status::Ok } else { Status::NotReady },
+        shutting_down: binding.shutting_down,
+        cached_loans: binding.cache.len(),
+        catalog_version: binding.catalog.version.clone(),
+        programs: binding.catalog.programs.len(),
+    };
+    // ответ 200 или 503
+    if ready {
+        (StatusCode::OK, Json(resp))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(resp))
+    }
+}
+
+/// Сведения о сборке и активном каталоге программ.
+#[utoipa::path(
+    get,
+    path = "/version",
+    responses((status = 200, description = "Сведения о сборке", body = BuildInfo))
+)]
+pub async fn version(State(state): State<SharedState>) -> Json<BuildInfo> {
+    Json(BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        commit: env!("GIT_COMMIT").to_string(),
+        build_time: env!("BUILD_TIME").to_string(),
+        catalog_version: state.read().unwrap().catalog.version.clone(),
+    })
+}
+
+/// Перезагрузка каталога программ и производственного календаря из файлов конфигурации.
+/// Замена каталога атомарна, расчеты в процессе завершаются по прежнему каталогу.
+#[utoipa::path(
+    post,
+    path = "/admin/catalog/reload",
+    responses(
+        (status = 200, description = "Каталог перезагружен", body = CatalogReload),
+        (status = 401, description = "Нет или неверный токен администратора, либо токен не задан",
+            body = ErrorResponse),
+        (status = 500, description = "Каталог не загружен, активный каталог не изменен",
+            body = ErrorResponse),
+    )
+)]
+pub async fn reload_catalog(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Json<CatalogReload>, AppError> {
+    // проверка токена, без заданного токена эндпоинт отключен
+    let token: String = state.read().unwrap().admin_token.clone();
+    let bearer: &[u8] = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .as_bytes();
+    if token.is_empty() || !constant_time_eq(bearer, token.as_bytes()) {
+        return Err(Unauthorized);
+    }
+    // чтение файлов вне асинхронного рантайма
+    let resp: CatalogReload = tokio::task::spawn_blocking(move || reload(&state, "admin"))
+        .await
+        .unwrap()
+        .map_err(|err| ReloadFailed(err.to_string()))?;
+    // ответ 200
+    Ok(Json(resp))
+}
+
+/// Сравнение токенов за время, не зависящее от совпадающего префикса.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Метрики сервиса в текстовом формате Prometheus.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Метрики Prometheus", body = String,
+        content_type = "text/plain"))
+)]
+pub async fn metrics(State(state): State<SharedState>) -> HttpResponse {
+    let (registry, cache_entries): (Arc<Metrics>, usize) = {
+        let binding = state.read().unwrap();
+        (Arc::clone(&binding.metrics), binding.cache.len())
+    };
+    (
+        [(header::CONTENT_TYPE, metrics::CONTENT_TYPE)],
+        registry.render(cache_entries),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::adapter::cache::AppState;
     use crate::adapter::router::router;
-    use crate::domain::mortgage::{Aggregates, Params, Program};
+    use crate::domain::calendar::DateShift;
+    use crate::domain::catalog::{RateBreakdown, MAX_MONTHS};
+    use crate::domain::mortgage::{Aggregates, Borrower, Program};
+    use crate::domain::schedule::DayCount;
+    use crate::errors::AppError::{
+        InvalidTerm, LineTooLarge, LoanProgramEmpty, MinInitialPayment, NotEnoughChildren,
+    };
     use crate::schema::Request as Req;
     use axum::{
         body::{Body, Bytes},
@@ -86,7 +644,9 @@ mod tests {
                 base: Some(true),
                 military: None,
                 salary: None,
+                family: None,
             },
+            ..Req::default()
         };
 
         let resp = router
@@ -102,6 +662,8 @@ mod tests {
             .unwrap();
 
         assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["deprecation"], "@1792368000");
+        assert_eq!(resp.headers()["sunset"], "Wed, 30 Jun 2027 00:00:00 GMT");
 
         let expected: Json<Response> = Json(Response {
             id: 0,
@@ -110,11 +672,23 @@ mod tests {
                     object_cost: 100.0,
                     initial_payment: 30.0,
                     months: 12,
+                    taxable_income: None,
+                    contributions: Vec::new(),
+                    borrower: Borrower::default(),
+                    co_borrowers: Vec::new(),
+                    options: Vec::new(),
+                    combined: false,
+                    disbursements: Vec::new(),
+                    issue_date: None,
+                    payment_day: None,
+                    day_count: DayCount::Monthly,
+                    date_shift: DateShift::None,
                 },
                 program: Program {
                     base: Some(true),
                     military: None,
                     salary: None,
+                    family: None,
                 },
                 aggregates: Aggregates {
                     rate: 10.0,
@@ -126,35 +700,1170 @@ mod tests {
                         .unwrap()
                         .format("%Y-%m-%d")
                         .to_string(),
+                    construction_interest: None,
+                    rate_breakdown: RateBreakdown {
+                        base_rate: 10.0,
+                        adjustments: Vec::new(),
+                    },
+                    payment_to_income: None,
                 },
+                deduction: None,
+                schedule: Vec::new(),
+                tranches: Vec::new(),
+                catalog_version: "builtin".to_string(),
+                calendar: Arc::default(),
             },
         });
 
         let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
         let actual: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(actual, json!(*expected));
+        // параметры, не переданные в запросе, в ответе v1 не появляются
+        let params: &Value = &actual["loan"]["params"];
+        for field in ["borrower", "combined", "day_count", "date_shift"] {
+            assert!(params.get(field).is_none(), "{field}");
+        }
     }
 
     #[tokio::test]
-    async fn test_cache() {
+    async fn test_mortgage_v1() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let req = json!({
+            "object_cost": 100.0,
+            "initial_payment": 30.0,
+            "months": 12,
+            "program": {"base": true}
+        });
+        let execute = |uri: &str| {
+            Request::builder()
+                .method(http::Method::POST)
+                .uri(uri)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&req).unwrap()))
+                .unwrap()
+        };
+
+        let resp = router.clone().oneshot(execute("/execute")).await.unwrap();
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let legacy: Value = serde_json::from_slice(&body).unwrap();
+
+        let resp = router.oneshot(execute("/v1/execute")).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!resp.headers().contains_key("deprecation"));
+        assert!(!resp.headers().contains_key("sunset"));
+
+        // ответ v1 совпадает с ответом пути без версии
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["id"], json!(1));
+        assert_eq!(actual["loan"], legacy["loan"]);
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_deduction() {
         let state: Arc<RwLock<AppState>> = SharedState::default();
         let router = router(state).await;
+        let req = Req {
+            object_cost: 5_000_000.0,
+            initial_payment: 1_000_000.0,
+            months: 240,
+            program: Program {
+                salary: Some(true),
+                ..Program::default()
+            },
+            taxable_income: Some(3_000_000.0),
+            borrower: Borrower {
+                salary_client: true,
+                ..Borrower::default()
+            },
+            ..Req::default()
+        };
 
         let resp = router
             .oneshot(
                 Request::builder()
-                    .method(http::Method::GET)
-                    .uri("/cache")
-                    .body(Body::empty())
+                    .method(http::Method::POST)
+                    .uri("/v1/execute")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&json!(req)).unwrap()))
                     .unwrap(),
             )
             .await
             .unwrap();
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        let total: f64 = actual["loan"]["deduction"]["total"].as_f64().unwrap();
+        assert!((total - 650_000.0).abs() < 1e-6);
+        assert_eq!(
+            actual["loan"]["params"]["taxable_income"],
+            json!(3_000_000.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_options() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let req = json!({
+            "object_cost": 100.0,
+            "initial_payment": 30.0,
+            "months": 12,
+            "program": {"base": true},
+            "options": ["no_life_insurance", "electronic_registration"]
+        });
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&req).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        let breakdown: &Value = &actual["loan"]["aggregates"]["rate_breakdown"];
+        assert_eq!(breakdown["base_rate"], json!(10.0));
+        assert_eq!(
+            breakdown["adjustments"],
+            json!([
+                {"factor": "no_life_insurance", "value": 1.0},
+                {"factor": "electronic_registration", "value": -0.1}
+            ])
+        );
+        let rate: f64 = actual["loan"]["aggregates"]["rate"].as_f64().unwrap();
+        assert!((rate - 10.9).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_compare() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let params = Params {
+            object_cost: 5_000_000.0,
+            initial_payment: 1_000_000.0,
+            months: 240,
+            borrower: Borrower {
+                military_service: true,
+                salary_client: true,
+                ..Borrower::default()
+            },
+            ..Params::default()
+        };
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/compare")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&json!(params)).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        let eligible: &Vec<Value> = actual["eligible"].as_array().unwrap();
+        let programs: Vec<&str> = eligible
+            .iter()
+            .map(|item| item["program"].as_str().unwrap())
+            .collect();
+        assert_eq!(programs, vec!["salary", "military", "base"]);
+        assert_eq!(eligible[0]["monthly_payment_diff"], json!(0.0));
+        assert_eq!(eligible[0]["total_cost_diff"], json!(0.0));
+        assert_eq!(eligible[2]["monthly_payment_diff"], json!(5143.0));
+        assert_eq!(eligible[2]["total_cost_diff"], json!(1_234_320.0));
+        assert_eq!(
+            actual["ineligible"],
+            json!([{"program": "family", "error": NotEnoughChildren.to_string()}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compare_ineligible() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let params = Params {
+            object_cost: 100.0,
+            initial_payment: 10.0,
+            months: 12,
+            borrower: Borrower {
+                salary_client: true,
+                ..Borrower::default()
+            },
+            ..Params::default()
+        };
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/compare")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&json!(params)).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["eligible"], json!([]));
+        assert_eq!(
+            actual["ineligible"][0],
+            json!({"program": "salary", "error": MinInitialPayment.to_string()})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compare_term() {
+        let router = router(SharedState::default()).await;
+        for months in [0, MAX_MONTHS + 1] {
+            let params = Params {
+                object_cost: 100.0,
+                initial_payment: 30.0,
+                months,
+                ..Params::default()
+            };
+
+            let resp = router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(http::Method::POST)
+                        .uri("/v1/compare")
+                        .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                        .body(Body::from(serde_json::to_vec(&json!(params)).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            // Err. Срок вне допустимого диапазона
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+            let actual: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(actual["error"], json!(InvalidTerm.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grid() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let params = GridParams {
+            loan_sum: 4_000_000.0,
+            rate_from: 8.0,
+            rate_to: 9.0,
+            rate_step: 0.5,
+            years_from: 20,
+            years_to: 20,
+            years_step: 1,
+        };
+
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/grid")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&json!(params)).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["rows"].as_array().unwrap().len(), 3);
+        assert_eq!(actual["rows"][0]["rate"], json!(8.0));
+        assert_eq!(
+            actual["rows"][0]["cells"][0]["monthly_payment"],
+            json!(33458.0)
+        );
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/grid?format=csv")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&json!(params)).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[http::header::CONTENT_TYPE],
+            mime::TEXT_CSV_UTF_8.as_ref()
+        );
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: String = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(actual.lines().count(), 4);
+        assert_eq!(actual.lines().nth(1), Some("8,20,33458,4029920"));
+    }
+
+    #[tokio::test]
+    async fn test_refinance() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let params = json!({
+            "balance": 4_000_000.0,
+            "property_value": 6_000_000.0,
+            "rate": 12.0,
+            "months_left": 240,
+            "program": {"salary": true},
+            "borrower": {"salary_client": true},
+            "fees": 100_000.0
+        });
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/refinance")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&params).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
 
         let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
         let actual: Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(actual, json!({"error": EmptyCache.to_string()}));
+        assert_eq!(
+            actual["new"]["aggregates"]["monthly_payment"],
+            json!(33458.0)
+        );
+        assert_eq!(actual["interest_saved"], json!(2_440_640.0));
+        assert_eq!(actual["break_even_month"], json!(10));
+    }
+
+    #[tokio::test]
+    async fn test_stress() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 5_000_000.0;
+        loan.params.initial_payment = 1_000_000.0;
+        loan.params.months = 240;
+        loan.execute(&state.read().unwrap().catalog).unwrap();
+        let id: u32 = insert(Arc::clone(&state), loan).await;
+        let router = router(state).await;
+        let params = json!({
+            "id": id,
+            "scenarios": [
+                {"shock": 1.0, "after_year": 3},
+                {"shock": 5.0, "after_year": 3}
+            ]
+        });
+
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/stress")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&params).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        let scenarios: &Vec<Value> = actual["scenarios"].as_array().unwrap();
+        assert_eq!(scenarios.len(), 2);
+        assert_eq!(scenarios[0]["rate"], json!(11.0));
+        assert!(
+            scenarios[1]["monthly_payment"].as_f64().unwrap()
+                > scenarios[0]["monthly_payment"].as_f64().unwrap()
+        );
+        assert_eq!(scenarios[1]["breach"], json!(false));
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/stress")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_vec(&json!({"id": 42, "scenarios": []})).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_simulate() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 5_000_000.0;
+        loan.params.initial_payment = 1_000_000.0;
+        loan.params.months = 240;
+        loan.execute(&state.read().unwrap().catalog).unwrap();
+        let id: u32 = insert(Arc::clone(&state), loan).await;
+        let router = router(state).await;
+        let params = json!({
+            "id": id,
+            "model": {"kind": "vasicek", "mean": 8.0, "reversion": 0.3, "volatility": 1.5},
+            "margin": 2.0,
+            "paths": 100,
+            "seed": 7
+        });
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/simulate")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&params).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["paths"], json!(100));
+        assert!(
+            actual["overpayment"]["p5"].as_f64().unwrap()
+                <= actual["overpayment"]["p95"].as_f64().unwrap()
+        );
+        assert!(actual.get("breach_probability").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/cache")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.headers()["deprecation"], "@1792368000");
+        assert_eq!(resp.headers()["sunset"], "Wed, 30 Jun 2027 00:00:00 GMT");
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["error"], json!(EmptyCache.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_cache_v1() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/v1/cache")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(!resp.headers().contains_key("deprecation"));
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["error"], json!(EmptyCache.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(Arc::clone(&state)).await;
+        let items = json!([
+            {"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"base": true}},
+            {"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {}},
+            {"object_cost": "100"},
+            {"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"salary": true}, "borrower": {"salary_client": true}}
+        ]);
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute/batch?cache=true")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&items).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        // порядок запросов сохраняется
+        assert_eq!(actual.as_array().unwrap().len(), 4);
+        assert_eq!(actual[0]["index"], json!(0));
+        assert_eq!(actual[0]["id"], json!(0));
+        assert_eq!(
+            actual[0]["loan"]["aggregates"]["monthly_payment"],
+            json!(7.0)
+        );
+        // ошибка расчета элемента
+        assert_eq!(actual[1]["error"], json!(LoanProgramEmpty.to_string()));
+        assert!(actual[1].get("loan").is_none());
+        // некорректный элемент
+        assert!(actual[2]["error"].is_string());
+        assert_eq!(actual[3]["id"], json!(1));
+        assert_eq!(actual[3]["loan"]["aggregates"]["rate"], json!(8.0));
+        // успешные расчеты сохранены в кэш
+        assert_eq!(state.read().unwrap().cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_too_large() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        state.write().unwrap().max_batch_size = 1;
+        let router = router(Arc::clone(&state)).await;
+        let items = json!([{}, {}]);
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute/batch")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&items).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            actual["error"],
+            json!(BatchTooLarge { max_size: 1 }.to_string())
+        );
+        assert!(state.read().unwrap().cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_body_limit() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        state.write().unwrap().http.body_limit_bytes = 16;
+        let router = router(Arc::clone(&state)).await;
+        let items = json!([{}, {}, {}, {}, {}, {}, {}, {}]);
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute/batch")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&items).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(state.read().unwrap().cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_stream() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(Arc::clone(&state)).await;
+        let body: &str = concat!(
+            r#"{"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"base": true}}"#,
+            "\n\n",
+            r#"{"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {}}"#,
+            "\n",
+            r#"{"object_cost": "100"}"#,
+        );
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute/stream?cache=true")
+                    .header(http::header::CONTENT_TYPE, ndjson::CONTENT_TYPE)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[http::header::CONTENT_TYPE],
+            ndjson::CONTENT_TYPE
+        );
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Vec<Value> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        // строка на каждый запрос в порядке поступления
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[0]["index"], json!(0));
+        assert_eq!(actual[0]["id"], json!(0));
+        assert_eq!(
+            actual[0]["loan"]["aggregates"]["monthly_payment"],
+            json!(7.0)
+        );
+        assert_eq!(actual[1]["index"], json!(1));
+        assert_eq!(actual[1]["error"], json!(LoanProgramEmpty.to_string()));
+        assert!(actual[2]["error"].is_string());
+        assert_eq!(state.read().unwrap().cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_stream_line_too_large() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        state.write().unwrap().http.body_limit_bytes = 128;
+        let router = router(Arc::clone(&state)).await;
+        // строка без перевода строки больше лимита и строка после нее
+        let body: String = format!(
+            "{}\n{}\n{}",
+            r#"{"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"base": true}}"#,
+            "x".repeat(1024),
+            r#"{"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"base": true}}"#,
+        );
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute/stream?cache=true")
+                    .header(http::header::CONTENT_TYPE, ndjson::CONTENT_TYPE)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Vec<Value> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        // Err. Ошибка строки больше лимита, дальше тело не читается
+        assert_eq!(actual.len(), 2);
+        assert!(actual[0]["loan"].is_object());
+        assert_eq!(actual[1]["index"], json!(1));
+        assert_eq!(
+            actual[1]["error"],
+            json!(LineTooLarge { max_size: 128 }.to_string())
+        );
+        assert_eq!(state.read().unwrap().cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stream() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(Arc::clone(&state)).await;
+        let request = || {
+            Request::builder()
+                .method(http::Method::GET)
+                .uri("/v1/cache/stream")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Err. Пустой кэш
+        let resp = router.clone().oneshot(request()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // Ok. Кредиты по строкам в порядке расчета
+        for months in [12, 24, 36] {
+            let mut loan: Mortgage = Mortgage::default();
+            loan.params.months = months;
+            insert(Arc::clone(&state), loan).await;
+        }
+        let resp = router.oneshot(request()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[http::header::CONTENT_TYPE],
+            ndjson::CONTENT_TYPE
+        );
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Vec<Response> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(
+            actual
+                .iter()
+                .map(|r| (r.id, r.loan.params.months))
+                .collect::<Vec<(u32, u16)>>(),
+            vec![(0, 12), (1, 24), (2, 36)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_healthz() {
+        let router = router(SharedState::default()).await;
+        let resp = router
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual, json!({"status": "ok"}));
+    }
+
+    #[tokio::test]
+    async fn test_readyz() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(Arc::clone(&state)).await;
+        insert(Arc::clone(&state), Mortgage::default()).await;
+
+        // Ok. Сервис готов
+        let resp = router
+            .clone()
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            actual,
+            json!({
+                "status": "ok",
+                "shutting_down": false,
+                "cached_loans": 1,
+                "catalog_version": "builtin",
+                "programs": 4
+            })
+        );
+
+        // Err. Сервис получил сигнал завершения
+        state.write().unwrap().shutting_down = true;
+        let resp = router
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Readiness = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual.status, Status::NotReady);
+        assert!(actual.shutting_down);
+    }
+
+    #[tokio::test]
+    async fn test_reload_catalog() {
+        let path = std::env::temp_dir().join(format!("mg-admin-{}.json", std::process::id()));
+        let mut catalog: Catalog = Catalog {
+            version: "2025-06".to_string(),
+            ..Catalog::default()
+        };
+        catalog.programs.get_mut(&ProgramKind::Base).unwrap().rate = 12_f64;
+        std::fs::write(&path, serde_json::to_vec(&catalog).unwrap()).unwrap();
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        {
+            let mut binding = state.write().unwrap();
+            binding.catalog_source.path = Some(path.clone());
+            binding.admin_token = "secret".to_string();
+        }
+        let router = router(Arc::clone(&state)).await;
+        let execute = || {
+            Request::builder()
+                .method(http::Method::POST)
+                .uri("/v2/execute")
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(
+                    r#"{"program": "base", "object_cost": 100, "initial_payment": 30, "months": 12}"#,
+                ))
+                .unwrap()
+        };
+        let reload = |token: &str| {
+            Request::post("/admin/catalog/reload")
+                .header(http::header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Ok. Расчет по встроенному каталогу
+        let resp = router.clone().oneshot(execute()).await.unwrap();
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: v2::Response = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual.catalog_version, "builtin");
+
+        // Err. Токен не задан - эндпоинт отключен, в том числе для пустого токена в запросе
+        state.write().unwrap().admin_token = String::new();
+        let resp = router.clone().oneshot(reload("")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::post("/admin/catalog/reload")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(state.read().unwrap().catalog.version, "builtin");
+
+        // Err. Неверный токен
+        state.write().unwrap().admin_token = "secret".to_string();
+        let resp = router.clone().oneshot(reload("wrong")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(state.read().unwrap().catalog.version, "builtin");
+
+        // Ok. Каталог перезагружен
+        let resp = router.clone().oneshot(reload("secret")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: CatalogReload = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            actual,
+            CatalogReload {
+                previous_version: "builtin".to_string(),
+                catalog_version: "2025-06".to_string(),
+                programs: 4,
+                holidays: 0,
+            }
+        );
+
+        // Ok. Новый расчет по новому каталогу, прежний расчет в кэше - со своей версией
+        let resp = router.clone().oneshot(execute()).await.unwrap();
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: v2::Response = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual.catalog_version, "2025-06");
+        assert_eq!(actual.rate, 12.into());
+        {
+            let binding = state.read().unwrap();
+            assert_eq!(binding.cache[&0].catalog_version, "builtin");
+            assert_eq!(binding.cache[&1].catalog_version, "2025-06");
+        }
+
+        // Err. Некорректный файл, активный каталог прежний
+        std::fs::write(&path, "{").unwrap();
+        let resp = router.oneshot(reload("secret")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(state.read().unwrap().catalog.version, "2025-06");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_version() {
+        let router = router(SharedState::default()).await;
+        let resp = router
+            .oneshot(Request::get("/version").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: BuildInfo = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual.version, env!("CARGO_PKG_VERSION"));
+        assert!(!actual.commit.is_empty());
+        assert!(chrono::DateTime::parse_from_rfc3339(&actual.build_time).is_ok());
+        assert_eq!(actual.catalog_version, "builtin");
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(Arc::clone(&state)).await;
+        let post = |uri: &str, body: Value| {
+            Request::builder()
+                .method(http::Method::POST)
+                .uri(uri)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        };
+        let ok: Value = json!({"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {"base": true}});
+        let empty: Value =
+            json!({"object_cost": 100.0, "initial_payment": 30.0, "months": 12, "program": {}});
+
+        for req in [
+            post("/v1/execute", ok.clone()),
+            post("/v1/execute", empty.clone()),
+            post("/v1/execute/batch", json!([ok, empty])),
+        ] {
+            router.clone().oneshot(req).await.unwrap();
+        }
+        let resp = router
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[http::header::CONTENT_TYPE],
+            metrics::CONTENT_TYPE
+        );
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: String = String::from_utf8(body.to_vec()).unwrap();
+        for line in [
+            "http_requests_total{route=\"/v1/execute\",method=\"POST\",status=\"200\"} 1",
+            "http_requests_total{route=\"/v1/execute\",method=\"POST\",status=\"400\"} 1",
+            "http_requests_total{route=\"/v1/execute/batch\",method=\"POST\",status=\"200\"} 1",
+            "http_request_duration_seconds_count{route=\"/v1/execute\",method=\"POST\",status=\"200\"} 1",
+            // расчеты и ошибки учитываются и для элементов пакета
+            "mortgage_calculations_total{program=\"base\"} 2",
+            "mortgage_errors_total{error=\"loan_program_empty\"} 2",
+            "mortgage_cache_entries 1",
+            "mortgage_cache_evictions_total 0",
+        ] {
+            assert!(actual.lines().any(|l| l == line), "{line}\n{actual}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_v2() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let req = json!({
+            "program": "base",
+            "object_cost": "5000000.00",
+            "initial_payment": "1000000.00",
+            "months": 300
+        });
+
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v2/execute")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&req).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["program"], json!("base"));
+        assert_eq!(actual["months"], json!(300));
+        assert_eq!(actual["loan_sum"], json!("4000000.00"));
+        assert_eq!(actual["rate"], json!("10"));
+
+        // расчет v2 доступен в кэше v1 и v2
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/v2/cache")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual[0]["id"], json!(0));
+        assert!(actual[0]["monthly_payment"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_v2_term() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+        let req = json!({
+            "program": "base",
+            "object_cost": 5_000_000,
+            "initial_payment": 1_000_000,
+            "months": 400
+        });
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v2/execute")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&req).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["error"], json!(InvalidTerm.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_mortgage_v1_term() {
+        let router = router(SharedState::default()).await;
+        let req = json!({
+            "object_cost": 100.0,
+            "initial_payment": 30.0,
+            "months": 0,
+            "program": {"base": true}
+        });
+
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::POST)
+                    .uri("/v1/execute")
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&req).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(actual["error"], json!(InvalidTerm.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_paths() {
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router = router(state).await;
+
+        // путь без версии работает как v1 и помечен устаревшим
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/cache")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.headers()["deprecation"], "@1792368000");
+        assert_eq!(resp.headers()["sunset"], "Wed, 30 Jun 2027 00:00:00 GMT");
+        assert_eq!(
+            resp.headers()[http::header::LINK],
+            "</v1/cache>; rel=\"successor-version\""
+        );
+
+        // пути с версией без заголовков
+        let resp = router
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/v1/cache")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(!resp.headers().contains_key("deprecation"));
+    }
+
+    #[tokio::test]
+    async fn test_request_id() {
+        let router = router(SharedState::default()).await;
+
+        // Ok. Id клиента возвращается в заголовке и в ответе с ошибкой
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(http::Method::GET)
+                    .uri("/v1/cache")
+                    .header("x-request-id", "client-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.headers()["x-request-id"], "client-42");
+        let body: Bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let actual: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            actual,
+            ErrorResponse {
+                error: EmptyCache.to_string(),
+                request_id: Some("client-42".to_string()),
+            }
+        );
+
+        // Ok. Без заголовка генерируется UUID
+        let resp = router
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let id: &str = resp.headers()["x-request-id"].to_str().unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.chars().nth(14), Some('4'));
+        assert_eq!(id.split('-').count(), 5);
     }
 }
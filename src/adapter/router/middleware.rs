@@ -1,32 +1,331 @@
-use axum::http::StatusCode;
+use crate::adapter::cache::SharedState;
+use crate::adapter::metrics::{Metrics, RequestLabels};
+use crate::adapter::telemetry::parent_context;
+use crate::config::CorsConfig;
+use crate::errors::AppError::RequestTimeout;
+use crate::errors::{ErrorKind, REQUEST_ID};
+use axum::extract::{MatchedPath, State};
+use axum::http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::IntoResponse;
 use axum::{extract::Request, middleware::Next, response::Response};
-use std::time::Instant;
-use tracing::{error, info};
+use rand::Rng;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, field, info, info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
-/// Middleware.
-pub async fn middleware(request: Request, next: Next) -> Response {
-    // эндпоит
-    let path: &String = &request.uri().path().to_string();
+// Дата объявления путей без версии устаревшими (RFC 9745), 2026-10-19.
+const DEPRECATION: &str = "@1792368000";
+// Дата отключения путей без версии (RFC 8594).
+const SUNSET: &str = "Wed, 30 Jun 2027 00:00:00 GMT";
+// Заголовок с id запроса.
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+// Максимальная длина id запроса от клиента.
+const MAX_REQUEST_ID_LEN: usize = 128;
+// Методы API для preflight-запросов CORS.
+const CORS_METHODS: &str = "GET, POST, OPTIONS";
+
+/// Middleware устаревших путей без версии: заголовки `Deprecation`, `Sunset`
+/// и ссылка на тот же эндпоинт в `/v1`.
+pub async fn deprecation(request: Request, next: Next) -> Response {
+    let successor: String = format!("</v1{}>; rel=\"successor-version\"", request.uri().path());
+    let mut response: Response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_static(DEPRECATION),
+    );
+    headers.insert(
+        HeaderName::from_static("sunset"),
+        HeaderValue::from_static(SUNSET),
+    );
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.insert(header::LINK, link);
+    }
+    response
+}
+
+/// Middleware логирования: id запроса (`X-Request-Id` клиента или новый),
+/// span запроса с этим id и структурированная запись об ответе.
+pub async fn middleware(mut request: Request, next: Next) -> Response {
+    // id запроса из заголовка или новый
+    let request_id: String = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map_or_else(new_request_id, str::to_string);
+    // заголовок для хендлеров, значение уже проверено или сгенерировано
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        request.headers_mut().insert(X_REQUEST_ID, value);
+    }
+    // эндпоит и метод
+    let path: String = request.uri().path().to_string();
+    let method: String = request.method().to_string();
+    // span запроса: родитель из W3C `traceparent` и имя по шаблону маршрута
+    let route: &str = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(path.as_str(), MatchedPath::as_str);
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        %method,
+        %path,
+        otel.kind = "server",
+        otel.name = %format!("{method} {route}"),
+        http.status_code = field::Empty,
+        otel.status_code = field::Empty,
+    );
+    span.set_parent(parent_context(request.headers()));
     // старт времени
     let start: Instant = Instant::now();
     // вызов хендлера
-    let response: Response = next.run(request).await;
+    let mut response: Response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .instrument(span.clone())
+        .await;
     // статус ответа хендлера
     let status: StatusCode = response.status();
+    span.record("http.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "error");
+    }
     // стоп времени
-    let end: u128 = start.elapsed().as_micros();
-    // логирование ответа хендлера
-    if response.status().is_success() {
+    let duration_us: u64 = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+    // логирование ответа хендлера, поля запроса пишутся и в событие:
+    // span уровня info отключается фильтром при более строгом уровне логов
+    if status.is_success() {
         info!(
-            "path={}, status=Success, status_code={}, duration={} μs",
-            path, status, end
+            %request_id,
+            %method,
+            %path,
+            status = "Success",
+            status_code = status.as_u16(),
+            duration_us,
+            "request completed"
         );
     } else {
         error!(
-            "path={}, status=Error, status_code={}, duration={} μs",
-            path, status, end
-        )
+            %request_id,
+            %method,
+            %path,
+            status = "Error",
+            status_code = status.as_u16(),
+            duration_us,
+            "request failed"
+        );
+    }
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(X_REQUEST_ID, value);
     }
     // ответ декоратора
     response
 }
+
+/// Новый id запроса: случайный UUID v4.
+fn new_request_id() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    let hex: String = bytes
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| match i {
+            6 => 0x40 | (byte & 0x0f),
+            8 => 0x80 | (byte & 0x3f),
+            _ => *byte,
+        })
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Middleware метрик: счетчик и длительность запросов по маршруту и статусу, ошибки по вариантам.
+pub async fn instrument(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // шаблон маршрута, чтобы не плодить метки по каждому пути
+    let route: String = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+    let method: String = request.method().to_string();
+    let start: Instant = Instant::now();
+    let response: Response = next.run(request).await;
+    let metrics: Arc<Metrics> = Arc::clone(&state.read().unwrap().metrics);
+    metrics.request(
+        RequestLabels {
+            route,
+            method,
+            status: response.status().as_u16(),
+        },
+        start.elapsed(),
+    );
+    if let Some(ErrorKind(kind)) = response.extensions().get::<ErrorKind>() {
+        metrics.error(kind);
+    }
+    response
+}
+
+/// Middleware таймаута: запрос, не получивший ответ за отведенное время, завершается с 408.
+/// Потоковые ответы ограничиваются только до начала передачи тела.
+pub async fn timeout(State(limit): State<Duration>, request: Request, next: Next) -> Response {
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => RequestTimeout {
+            secs: limit.as_secs(),
+        }
+        .into_response(),
+    }
+}
+
+/// Middleware CORS: ответ на preflight-запросы и заголовки для разрешенных источников.
+pub async fn cors(State(cors): State<Arc<CorsConfig>>, request: Request, next: Next) -> Response {
+    let origin: Option<HeaderValue> = request
+        .headers()
+        .get(header::ORIGIN)
+        .filter(|origin| origin.to_str().is_ok_and(|origin| cors.allows(origin)))
+        .cloned();
+    let Some(origin) = origin else {
+        return next.run(request).await;
+    };
+    // preflight: браузер спрашивает разрешение до основного запроса
+    if request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        let mut response: Response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static(CORS_METHODS),
+        );
+        if let Some(requested) = request
+            .headers()
+            .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, requested.clone());
+        }
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, cors.max_age_secs.into());
+        allow_origin(&mut response, origin);
+        return response;
+    }
+    let mut response: Response = next.run(request).await;
+    response.headers_mut().insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("x-request-id"),
+    );
+    allow_origin(&mut response, origin);
+    response
+}
+
+/// Разрешенный источник в ответе, ответ зависит от заголовка `Origin`.
+fn allow_origin(response: &mut Response, origin: HeaderValue) {
+    let headers = response.headers_mut();
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::middleware::from_fn_with_state;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn call(router: Router, request: axum::http::Request<Body>) -> Response {
+        router.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let router: Router = Router::new()
+            .route("/fast", get(|| async { "ok" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    "ok"
+                }),
+            )
+            .layer(from_fn_with_state(Duration::from_millis(20), timeout));
+        // Ok. Ответ в пределах таймаута
+        let request = axum::http::Request::get("/fast")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(call(router.clone(), request).await.status(), StatusCode::OK);
+        // Err. Таймаут
+        let request = axum::http::Request::get("/slow")
+            .body(Body::empty())
+            .unwrap();
+        let response: Response = call(router, request).await;
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(
+            response.extensions().get::<ErrorKind>(),
+            Some(&ErrorKind("request_timeout"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cors() {
+        let config: Arc<CorsConfig> = Arc::new(CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            max_age_secs: 60,
+        });
+        let router: Router = Router::new()
+            .route("/cache", get(|| async { "ok" }))
+            .layer(from_fn_with_state(config, cors));
+        // Ok. Preflight разрешенного источника
+        let request = axum::http::Request::options("/cache")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap();
+        let response: Response = call(router.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], CORS_METHODS);
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "60");
+        // Ok. Простой запрос разрешенного источника
+        let request = axum::http::Request::get("/cache")
+            .header(header::ORIGIN, "https://app.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response: Response = call(router.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        // Err. Источник не разрешен - без заголовков CORS
+        let request = axum::http::Request::get("/cache")
+            .header(header::ORIGIN, "https://evil.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response: Response = call(router, request).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
@@ -1,23 +1,118 @@
-use crate::adapter::cache::AppState;
-use crate::adapter::router::handler::{cache, mortgage};
-use crate::adapter::router::middleware::middleware;
+use crate::adapter::cache::{AppState, SharedState};
+use crate::adapter::router::handler::{
+    batch, cache, cache_stream, cache_v2, compare, grid, healthz, metrics, mortgage,
+    mortgage_stream, mortgage_v2, readyz, refinance, reload_catalog, simulate, stress, version,
+};
+use crate::adapter::router::middleware::{cors, deprecation, instrument, middleware, timeout};
+use crate::adapter::router::openapi::openapi;
+use crate::config::HttpConfig;
 use axum::{
+    extract::DefaultBodyLimit,
+    handler::Handler,
+    http::Method,
     middleware::{self as mw},
-    routing::{get, post},
+    routing::{on, MethodFilter, MethodRouter},
     Router,
 };
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use utoipa_swagger_ui::SwaggerUi;
 
 mod handler;
 mod middleware;
+mod ndjson;
+mod openapi;
+
+/// Маршрут: метод, путь и хендлер. Таблицы маршрутов сверяются со спецификацией OpenAPI.
+struct Route {
+    // метод нужен для сверки со спецификацией, роутер берет его из хендлера
+    #[cfg_attr(not(test), allow(dead_code))]
+    method: Method,
+    path: &'static str,
+    handler: MethodRouter<SharedState>,
+}
+
+/// Маршрут хендлера для метода.
+fn route<H, T>(method: Method, path: &'static str, handler: H) -> Route
+where
+    H: Handler<T, SharedState>,
+    T: 'static,
+{
+    let filter: MethodFilter = MethodFilter::try_from(method.clone()).unwrap();
+    Route {
+        method,
+        path,
+        handler: on(filter, handler),
+    }
+}
+
+/// Регистрация маршрутов в роутере.
+fn routes(routes: Vec<Route>) -> Router<SharedState> {
+    routes.into_iter().fold(Router::new(), |router, route| {
+        router.route(route.path, route.handler)
+    })
+}
+
+/// Хендлеры API v1.
+fn v1_routes() -> Vec<Route> {
+    vec![
+        route(Method::POST, "/execute", mortgage),
+        route(Method::POST, "/execute/batch", batch),
+        route(Method::POST, "/execute/stream", mortgage_stream),
+        route(Method::POST, "/compare", compare),
+        route(Method::POST, "/grid", grid),
+        route(Method::POST, "/refinance", refinance),
+        route(Method::POST, "/stress", stress),
+        route(Method::POST, "/simulate", simulate),
+        route(Method::GET, "/cache", cache),
+        route(Method::GET, "/cache/stream", cache_stream),
+    ]
+}
+
+/// Хендлеры API v2.
+fn v2_routes() -> Vec<Route> {
+    vec![
+        route(Method::POST, "/execute", mortgage_v2),
+        route(Method::GET, "/cache", cache_v2),
+    ]
+}
+
+/// Пробы, сведения о сборке, метрики и администрирование.
+fn service_routes() -> Vec<Route> {
+    vec![
+        route(Method::GET, "/healthz", healthz),
+        route(Method::GET, "/readyz", readyz),
+        route(Method::GET, "/version", version),
+        route(Method::GET, "/metrics", metrics),
+        route(Method::POST, "/admin/catalog/reload", reload_catalog),
+    ]
+}
 
 /// Создание роутера и регистрация хендлеров.
+/// Таймаут, лимит тела запроса и CORS берутся из настроек `state` при создании.
 pub async fn router(state: Arc<RwLock<AppState>>) -> Router {
-    Router::new()
-        .route("/execute", post(mortgage))
-        .route("/cache", get(cache))
+    let http: HttpConfig = state.read().unwrap().http.clone();
+    let mut router: Router<SharedState> = Router::new()
+        .nest("/v1", routes(v1_routes()))
+        .nest("/v2", routes(v2_routes()))
+        // устаревшие пути без версии
+        .merge(routes(v1_routes()).layer(mw::from_fn(deprecation)))
+        // пробы, сведения о сборке и администрирование
+        .merge(routes(service_routes()))
+        // спецификация OpenAPI и Swagger UI
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", openapi()));
+    // таймаут обработки запроса
+    if http.request_timeout_secs > 0 {
+        router = router.layer(mw::from_fn_with_state(
+            Duration::from_secs(http.request_timeout_secs),
+            timeout,
+        ));
+    }
+    let mut router: Router = router
         // кастомный middleware
         .layer(mw::from_fn(middleware))
+        // метрики Prometheus
+        .layer(mw::from_fn_with_state(Arc::clone(&state), instrument))
         // // axum-логер
         // .layer((
         //     TraceLayer::new_for_http()
@@ -26,5 +121,12 @@ pub async fn router(state: Arc<RwLock<AppState>>) -> Router {
         //     // Graceful shutdown
         //     TimeoutLayer::new(Duration::from_secs(5)),
         // ))
-        .with_state(Arc::clone(&state))
+        // лимит тела запроса для экстракторов
+        .layer(DefaultBodyLimit::max(http.body_limit_bytes))
+        .with_state(Arc::clone(&state));
+    // CORS для разрешенных источников
+    if !http.cors.allowed_origins.is_empty() {
+        router = router.layer(mw::from_fn_with_state(Arc::new(http.cors), cors));
+    }
+    router
 }
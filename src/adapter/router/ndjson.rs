@@ -0,0 +1,123 @@
+use crate::errors::AppError;
+use crate::errors::AppError::LineTooLarge;
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::future::ready;
+
+// Тип содержимого NDJSON (JSON-документ в каждой строке).
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Строки тела запроса по мере поступления данных, без буферизации всего тела.
+/// Пустые строки пропускаются, ошибка чтения тела завершает поток. Строка длиннее `max_len`
+/// байт возвращается ошибкой, после нее поток завершается: буфер не растет без ограничений.
+pub fn lines(body: Body, max_len: usize) -> impl Stream<Item = Result<Vec<u8>, AppError>> + Send {
+    stream::unfold(
+        (body.into_data_stream(), Vec::new(), false),
+        move |(mut data, mut buf, mut done)| async move {
+            loop {
+                if let Some(pos) = buf.iter().position(|b: &u8| *b == b'\n') {
+                    if pos > max_len {
+                        buf.clear();
+                        return Some((Err(LineTooLarge { max_size: max_len }), (data, buf, true)));
+                    }
+                    let line: Vec<u8> = buf.drain(..=pos).collect();
+                    return Some((Ok(line), (data, buf, done)));
+                }
+                if buf.len() > max_len {
+                    buf.clear();
+                    return Some((Err(LineTooLarge { max_size: max_len }), (data, buf, true)));
+                }
+                if done {
+                    return (!buf.is_empty())
+                        .then(|| (Ok(std::mem::take(&mut buf)), (data, buf, done)));
+                }
+                match data.next().await {
+                    Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+                    _ => done = true,
+                }
+            }
+        },
+    )
+    .filter(|line: &Result<Vec<u8>, AppError>| {
+        ready(
+            line.as_ref()
+                .map_or(true, |line| !line.trim_ascii().is_empty()),
+        )
+    })
+}
+
+/// Потоковый ответ NDJSON: каждый элемент сериализуется в отдельную строку.
+pub fn response<T, S>(items: S) -> Response
+where
+    T: Serialize,
+    S: Stream<Item = T> + Send + 'static,
+{
+    let body: Body = Body::from_stream(items.map(|item: T| {
+        let mut line: Vec<u8> = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+        Ok::<Vec<u8>, serde_json::Error>(line)
+    }));
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use std::convert::Infallible;
+
+    #[tokio::test]
+    async fn test_lines() {
+        // строки, разбитые на произвольные части
+        let chunks: Vec<Result<&str, Infallible>> = vec![
+            Ok("{\"a\":"),
+            Ok("1}\n\n{\"b\""),
+            Ok(":2}\r\n  \n{\"c\":3}"),
+        ];
+        let body: Body = Body::from_stream(stream::iter(chunks));
+        let actual: Vec<Vec<u8>> = lines(body, 16).map(Result::unwrap).collect().await;
+        assert_eq!(
+            actual,
+            vec![
+                b"{\"a\":1}\n".to_vec(),
+                b"{\"b\":2}\r\n".to_vec(),
+                b"{\"c\":3}".to_vec()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lines_too_large() {
+        // Err. Строка без перевода строки длиннее лимита, чтение прекращается
+        let chunks: Vec<Result<&str, Infallible>> = vec![
+            Ok("{\"a\":1}\n"),
+            Ok("{\"b\":\"xxxx"),
+            Ok("xxxxxxxxxx"),
+            Ok("\"}\n{\"c\":3}\n"),
+        ];
+        let body: Body = Body::from_stream(stream::iter(chunks));
+        let actual: Vec<Result<Vec<u8>, AppError>> = lines(body, 16).collect().await;
+        assert_eq!(
+            actual,
+            vec![
+                Ok(b"{\"a\":1}\n".to_vec()),
+                Err(LineTooLarge { max_size: 16 })
+            ]
+        );
+        // Err. Длинная строка в одной части тела
+        let body: Body = Body::from("{\"b\":\"xxxxxxxxxxxxxxxx\"}\n{\"c\":3}\n");
+        let actual: Vec<Result<Vec<u8>, AppError>> = lines(body, 16).collect().await;
+        assert_eq!(actual, vec![Err(LineTooLarge { max_size: 16 })]);
+    }
+
+    #[tokio::test]
+    async fn test_response() {
+        let resp: Response = response(stream::iter(vec![1, 2, 3]));
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "1\n2\n3\n");
+    }
+}
@@ -0,0 +1,408 @@
+use crate::adapter::router::handler;
+use crate::errors::ErrorResponse;
+use utoipa::openapi::{Deprecated, OpenApi as Spec};
+use utoipa::OpenApi;
+
+/// Общие сведения, пробы и компоненты спецификации.
+#[derive(OpenApi)]
+#[openapi(
+    info(description = "Ипотечный калькулятор"),
+    paths(
+        handler::healthz,
+        handler::readyz,
+        handler::version,
+        handler::metrics,
+        handler::reload_catalog
+    ),
+    components(schemas(ErrorResponse))
+)]
+struct ApiDoc;
+
+/// Эндпоинты API v1.
+#[derive(OpenApi)]
+#[openapi(paths(
+    handler::mortgage,
+    handler::batch,
+    handler::mortgage_stream,
+    handler::compare,
+    handler::grid,
+    handler::refinance,
+    handler::stress,
+    handler::simulate,
+    handler::cache,
+    handler::cache_stream,
+))]
+struct V1;
+
+/// Эндпоинты API v2.
+#[derive(OpenApi)]
+#[openapi(paths(handler::mortgage_v2, handler::cache_v2))]
+struct V2;
+
+/// Спецификация OpenAPI сервиса: `/v1`, `/v2` и устаревшие пути без версии.
+pub fn openapi() -> Spec {
+    let mut legacy: Spec = V1::openapi();
+    for item in legacy.paths.paths.values_mut() {
+        for operation in [&mut item.get, &mut item.post].into_iter().flatten() {
+            operation.deprecated = Some(Deprecated::True);
+            operation.operation_id = operation
+                .operation_id
+                .as_ref()
+                .map(|id| format!("{id}_deprecated"));
+        }
+    }
+
+    ApiDoc::openapi()
+        .nest("/v1", V1::openapi())
+        .nest("/v2", V2::openapi())
+        .merge_from(legacy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::cache::{AppState, SharedState};
+    use crate::adapter::router::{router, service_routes, v1_routes, v2_routes, Route};
+    use crate::domain::catalog::MAX_MONTHS;
+    use axum::{
+        body::{Body, Bytes},
+        http::{self, Method, Request, StatusCode},
+        Router,
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, RwLock};
+    use tower::ServiceExt;
+
+    /// Запрос к сервису: статус и тело ответа.
+    async fn call(router: &Router, method: Method, uri: &str, body: Value) -> (StatusCode, Bytes) {
+        let resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status: StatusCode = resp.status();
+        (status, resp.into_body().collect().await.unwrap().to_bytes())
+    }
+
+    /// Проверка значения по схеме спецификации, возвращает расхождения.
+    fn validate(spec: &Value, schema: &Value, value: &Value, path: &str) -> Vec<String> {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name: &str = reference.trim_start_matches("#/components/schemas/");
+            return validate(spec, &spec["components"]["schemas"][name], value, path);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(variants) = schema[key].as_array() {
+                if variants
+                    .iter()
+                    .any(|variant| validate(spec, variant, value, path).is_empty())
+                {
+                    return Vec::new();
+                }
+                return vec![format!("{path}: no {key} variant matches {value}")];
+            }
+        }
+        if let Some(parts) = schema["allOf"].as_array() {
+            return parts
+                .iter()
+                .flat_map(|part| validate(spec, part, value, path))
+                .collect();
+        }
+        if let Some(variants) = schema["enum"].as_array() {
+            if !variants.contains(value) {
+                return vec![format!("{path}: {value} is not in enum")];
+            }
+        }
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => return Vec::new(),
+        };
+        let matches = |t: &str| match t {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => false,
+        };
+        if !types.iter().any(|t| matches(t)) {
+            return vec![format!("{path}: {value} is not {types:?}")];
+        }
+        let mut errors: Vec<String> = Vec::new();
+        if let Some(items) = value.as_array() {
+            for (i, item) in items.iter().enumerate() {
+                errors.extend(validate(
+                    spec,
+                    &schema["items"],
+                    item,
+                    &format!("{path}[{i}]"),
+                ));
+            }
+        }
+        if let Some(fields) = value.as_object() {
+            for (key, field) in fields {
+                let field_path: String = format!("{path}.{key}");
+                match schema["properties"].get(key) {
+                    Some(property) => errors.extend(validate(spec, property, field, &field_path)),
+                    None if schema["additionalProperties"].is_object() => errors.extend(validate(
+                        spec,
+                        &schema["additionalProperties"],
+                        field,
+                        &field_path,
+                    )),
+                    None => errors.push(format!("{field_path}: not in spec")),
+                }
+            }
+            for required in schema["required"].as_array().into_iter().flatten() {
+                if !fields.contains_key(required.as_str().unwrap_or_default()) {
+                    errors.push(format!("{path}.{required}: required but missing"));
+                }
+            }
+        }
+        errors
+    }
+
+    /// Проверка ответа хендлера по спецификации операции.
+    async fn check(
+        spec: &Value,
+        router: &Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+        expected: StatusCode,
+    ) {
+        let path: &str = uri.split('?').next().unwrap();
+        let operation: &Value = &spec["paths"][path][method.as_str().to_lowercase()];
+        let (status, bytes) = call(router, method, uri, body).await;
+        assert_eq!(status, expected, "{uri}");
+        let schema: &Value =
+            &operation["responses"][status.as_str()]["content"]["application/json"]["schema"];
+        assert!(schema.is_object(), "{uri}: no schema for {status}");
+        let value: Value = serde_json::from_slice(&bytes).unwrap();
+        let errors: Vec<String> = validate(spec, schema, &value, "$");
+        assert!(errors.is_empty(), "{uri}: {errors:#?}");
+    }
+
+    #[tokio::test]
+    async fn test_openapi_json() {
+        let router: Router = router(SharedState::default()).await;
+        let (status, bytes) = call(&router, Method::GET, "/openapi.json", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        let actual: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(actual, serde_json::to_value(openapi()).unwrap());
+        // Swagger UI
+        let (status, _) = call(&router, Method::GET, "/docs/", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_openapi() {
+        let spec: Value = serde_json::to_value(openapi()).unwrap();
+        // Ok. Пути с версией
+        assert!(spec["paths"]["/v1/execute"]["post"].is_object());
+        assert!(spec["paths"]["/v2/execute"]["post"].is_object());
+        assert!(spec["paths"]["/v1/execute"]["post"]["deprecated"].is_null());
+        // Ok. Пути без версии устаревшие
+        assert_eq!(spec["paths"]["/execute"]["post"]["deprecated"], json!(true));
+        assert_eq!(
+            spec["paths"]["/execute"]["post"]["operationId"],
+            json!("mortgage_deprecated")
+        );
+        // Ok. Сроки рефинансирования до максимального срока кредита
+        let refinance: &Value = &spec["components"]["schemas"]["RefinanceParams"]["properties"];
+        assert_eq!(refinance["months_left"]["maximum"], json!(MAX_MONTHS));
+        assert_eq!(refinance["months"]["maximum"], json!(MAX_MONTHS));
+        // Ok. Потоковые эндпоинты в формате NDJSON
+        assert!(
+            spec["paths"]["/v1/execute/stream"]["post"]["requestBody"]["content"]
+                ["application/x-ndjson"]
+                .is_object()
+        );
+        assert!(
+            spec["paths"]["/v1/cache/stream"]["get"]["responses"]["200"]["content"]
+                ["application/x-ndjson"]
+                .is_object()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spec_routes() {
+        // каждая операция спецификации зарегистрирована в роутере
+        let router: Router = router(SharedState::default()).await;
+        let spec: Value = serde_json::to_value(openapi()).unwrap();
+        for (path, operations) in spec["paths"].as_object().unwrap() {
+            for method in operations.as_object().unwrap().keys() {
+                let method: Method = method.to_uppercase().parse().unwrap();
+                let (status, bytes) = call(&router, method.clone(), path, json!({})).await;
+                assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{method} {path}");
+                assert!(
+                    status != StatusCode::NOT_FOUND || !bytes.is_empty(),
+                    "{method} {path}"
+                );
+                // ошибка хендлера описана в ответах операции
+                let error: Option<ErrorResponse> = serde_json::from_slice(&bytes).ok();
+                if status.is_client_error() && error.is_some() {
+                    let response: &Value =
+                        &operations[method.as_str().to_lowercase()]["responses"][status.as_str()];
+                    assert!(response.is_object(), "{method} {path}: no {status}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_router_routes() {
+        // каждый маршрут роутера описан в спецификации
+        let spec: Value = serde_json::to_value(openapi()).unwrap();
+        let tables: [(&str, Vec<Route>); 4] = [
+            ("/v1", v1_routes()),
+            ("/v2", v2_routes()),
+            ("", v1_routes()),
+            ("", service_routes()),
+        ];
+        for (prefix, routes) in tables {
+            for route in routes {
+                let path: String = format!("{prefix}{}", route.path);
+                let method: String = route.method.as_str().to_lowercase();
+                assert!(spec["paths"][&path][&method].is_object(), "{method} {path}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_spec_errors() {
+        // ошибки клиента описаны схемой ErrorResponse
+        let spec: Value = serde_json::to_value(openapi()).unwrap();
+        let reference: Value = json!("#/components/schemas/ErrorResponse");
+        for (path, operations) in spec["paths"].as_object().unwrap() {
+            for (method, operation) in operations.as_object().unwrap() {
+                for (status, response) in operation["responses"].as_object().unwrap() {
+                    if !status.starts_with('4') {
+                        continue;
+                    }
+                    let schema: &Value = &response["content"]["application/json"]["schema"];
+                    assert_eq!(schema["$ref"], reference, "{method} {path} {status}");
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spec_responses() {
+        // ответы хендлеров соответствуют схемам спецификации
+        let state: Arc<RwLock<AppState>> = SharedState::default();
+        let router: Router = router(state).await;
+        let spec: Value = serde_json::to_value(openapi()).unwrap();
+        let request: Value = json!({
+            "object_cost": 5_000_000,
+            "initial_payment": 1_000_000,
+            "months": 240,
+            "program": {"base": true},
+            "taxable_income": 1_200_000,
+            "options": ["electronic_registration"],
+            "borrower": {"income": 120_000, "date_of_birth": "1990-01-01"},
+            "issue_date": "2024-01-20",
+            "payment_day": 5,
+            "day_count": "act_365",
+            "date_shift": "following"
+        });
+
+        let ok: StatusCode = StatusCode::OK;
+        check(
+            &spec,
+            &router,
+            Method::POST,
+            "/v1/execute",
+            request.clone(),
+            ok,
+        )
+        .await;
+        let bad: Value =
+            json!({"object_cost": 1, "initial_payment": 0, "months": 12, "program": {}});
+        check(
+            &spec,
+            &router,
+            Method::POST,
+            "/v1/execute",
+            bad,
+            StatusCode::BAD_REQUEST,
+        )
+        .await;
+        let mut params: Value = request.clone();
+        params.as_object_mut().unwrap().remove("program");
+        check(&spec, &router, Method::POST, "/v1/compare", params, ok).await;
+        let grid: Value = json!({
+            "loan_sum": 4_000_000,
+            "rate_from": 8,
+            "rate_to": 10,
+            "rate_step": 1,
+            "years_from": 10,
+            "years_to": 20,
+            "years_step": 5
+        });
+        check(&spec, &router, Method::POST, "/v1/grid", grid, ok).await;
+        let refinance: Value = json!({
+            "balance": 4_000_000,
+            "rate": 12,
+            "months_left": 240,
+            "program": {"base": true}
+        });
+        check(&spec, &router, Method::POST, "/v1/refinance", refinance, ok).await;
+        let stress: Value = json!({"id": 0, "scenarios": [{"shock": 5, "after_year": 3}]});
+        check(&spec, &router, Method::POST, "/v1/stress", stress, ok).await;
+        let simulation: Value = json!({
+            "id": 0,
+            "model": {"kind": "vasicek", "mean": 8, "reversion": 0.3, "volatility": 1.5},
+            "margin": 2,
+            "paths": 10,
+            "seed": 1
+        });
+        check(&spec, &router, Method::POST, "/v1/simulate", simulation, ok).await;
+        let missing: Value = json!({"id": 100, "scenarios": []});
+        check(
+            &spec,
+            &router,
+            Method::POST,
+            "/v1/stress",
+            missing,
+            StatusCode::NOT_FOUND,
+        )
+        .await;
+        let batch: Value = json!([request, {"program": {}}]);
+        check(&spec, &router, Method::POST, "/v1/execute/batch", batch, ok).await;
+        check(&spec, &router, Method::GET, "/v1/cache", Value::Null, ok).await;
+        let v2: Value = json!({
+            "program": "base",
+            "object_cost": "5000000.00",
+            "initial_payment": "1000000.00",
+            "months": 300,
+            "day_count": "act_act"
+        });
+        check(&spec, &router, Method::POST, "/v2/execute", v2, ok).await;
+        check(&spec, &router, Method::GET, "/v2/cache", Value::Null, ok).await;
+        check(&spec, &router, Method::GET, "/cache", Value::Null, ok).await;
+        check(&spec, &router, Method::GET, "/healthz", Value::Null, ok).await;
+        check(&spec, &router, Method::GET, "/readyz", Value::Null, ok).await;
+        check(&spec, &router, Method::GET, "/version", Value::Null, ok).await;
+        // без токена администратора эндпоинт отключен
+        check(
+            &spec,
+            &router,
+            Method::POST,
+            "/admin/catalog/reload",
+            Value::Null,
+            StatusCode::UNAUTHORIZED,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,272 @@
+use axum::http::HeaderMap;
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry_http::HeaderExtractor;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{BatchConfigBuilder, BatchSpanProcessor, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use std::time::Duration;
+use tracing::warn;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::Registry;
+
+// Размер очереди спанов на экспорт, при переполнении спаны отбрасываются.
+const QUEUE_SIZE: usize = 4096;
+// Максимальное количество спанов в одном запросе к коллектору.
+const BATCH_SIZE: usize = 512;
+// Период отправки спанов.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+// Таймаут запроса к коллектору.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+// Путь приема спанов OTLP/HTTP относительно базового адреса коллектора.
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Слой `tracing`: спаны сервиса передаются в OpenTelemetry.
+pub type OtlpLayer = OpenTelemetryLayer<Registry, Tracer>;
+
+/// Экспортер спанов в коллектор OTLP.
+#[derive(Debug, Clone)]
+pub struct Exporter {
+    provider: TracerProvider,
+}
+
+impl Exporter {
+    /// Отправка накопленных спанов коллектору и остановка экспорта.
+    pub async fn flush(self) {
+        let provider: TracerProvider = self.provider;
+        // остановка пакетного экспорта блокирует поток до отправки последней пачки
+        if let Ok(Err(err)) = tokio::task::spawn_blocking(move || provider.shutdown()).await {
+            warn!(error = %err, "failed to export spans on shutdown");
+        }
+    }
+}
+
+/// Адрес приема спанов по базовому адресу коллектора (`OTEL_EXPORTER_OTLP_ENDPOINT`).
+fn traces_endpoint(endpoint: &str) -> String {
+    format!("{}{TRACES_PATH}", endpoint.trim_end_matches('/'))
+}
+
+/// Слой трассировки и экспортер спанов в коллектор OTLP/HTTP (protobuf) по базовому адресу `endpoint`.
+/// Экспорт выполняется фоновой задачей, нужен запущенный рантайм tokio.
+/// Устанавливает глобальный пропагатор W3C Trace Context.
+pub fn otlp(endpoint: &str, service_name: &str) -> Result<(OtlpLayer, Exporter), TraceError> {
+    let exporter: SpanExporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(traces_endpoint(endpoint))
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()?;
+    let batch = BatchConfigBuilder::default()
+        .with_max_queue_size(QUEUE_SIZE)
+        .with_max_export_batch_size(BATCH_SIZE)
+        .with_scheduled_delay(EXPORT_INTERVAL)
+        .with_max_export_timeout(EXPORT_TIMEOUT)
+        .build();
+    let processor = BatchSpanProcessor::builder(exporter, runtime::Tokio)
+        .with_batch_config(batch)
+        .build();
+    let provider: TracerProvider = TracerProvider::builder()
+        .with_span_processor(processor)
+        .with_resource(resource(service_name))
+        .build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Ok((layer(&provider), Exporter { provider }))
+}
+
+/// Атрибуты сервиса в спанах.
+fn resource(service_name: &str) -> Resource {
+    Resource::new([
+        KeyValue::new("service.name", service_name.to_string()),
+        KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ])
+}
+
+/// Слой `tracing` с трассировщиком сервиса.
+fn layer(provider: &TracerProvider) -> OtlpLayer {
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+}
+
+/// Контекст трассировки вызывающего сервиса из заголовков W3C `traceparent` и `tracestate`.
+/// Без экспорта пропагатор не установлен и контекст пустой.
+pub fn parent_context(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+    use opentelemetry::propagation::TextMapPropagator;
+    use opentelemetry::trace::{SpanKind, Status, TraceContextExt};
+    use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter as Export};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tracing::subscriber::with_default;
+    use tracing::{debug_span, field, info_span};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const TRACEPARENT_HEADER: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    /// Экспортер в память для проверки спанов.
+    #[derive(Debug, Clone, Default)]
+    struct Memory(Arc<Mutex<Vec<SpanData>>>);
+
+    impl Export for Memory {
+        fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+            self.0.lock().unwrap().extend(batch);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Контекст из заголовка `traceparent`.
+    fn context(traceparent: &str) -> Context {
+        let mut headers: HeaderMap = HeaderMap::new();
+        headers.insert("traceparent", traceparent.parse().unwrap());
+        TraceContextPropagator::new().extract(&HeaderExtractor(&headers))
+    }
+
+    #[test]
+    fn test_traces_endpoint() {
+        // Ok. Путь по умолчанию
+        assert_eq!(
+            traces_endpoint("http://collector:4318"),
+            "http://collector:4318/v1/traces"
+        );
+        // Ok. Базовый путь
+        assert_eq!(
+            traces_endpoint("https://localhost:4318/otel/"),
+            "https://localhost:4318/otel/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_parent_context() {
+        // Ok. Заголовок W3C
+        let actual = context(TRACEPARENT_HEADER);
+        let span = actual.span();
+        assert_eq!(
+            span.span_context().trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(
+            span.span_context().span_id().to_string(),
+            "00f067aa0ba902b7"
+        );
+        assert!(span.span_context().is_sampled());
+        // Err. Нулевой trace-id
+        let actual = context("00-00000000000000000000000000000000-00f067aa0ba902b7-01");
+        assert!(!actual.span().span_context().is_valid());
+    }
+
+    #[test]
+    fn test_layer() {
+        let memory: Memory = Memory::default();
+        let provider: TracerProvider = TracerProvider::builder()
+            .with_simple_exporter(memory.clone())
+            .with_resource(resource("mg-test"))
+            .build();
+        let subscriber = Registry::default().with(layer(&provider));
+
+        with_default(subscriber, || {
+            let root = info_span!(
+                "request",
+                otel.kind = "server",
+                otel.name = "POST /v1/execute",
+                otel.status_code = field::Empty
+            );
+            root.set_parent(context(TRACEPARENT_HEADER));
+            root.in_scope(|| {
+                let step = debug_span!("rate_calc", rate = 10_f64);
+                step.in_scope(|| tracing::debug!(error = "invalid"));
+            });
+            root.record("otel.status_code", "error");
+        });
+
+        let actual: Vec<SpanData> = memory.0.lock().unwrap().clone();
+        // дочерний спан закрывается первым
+        let [step, root] = actual.as_slice() else {
+            panic!("{actual:#?}");
+        };
+        // Ok. Родитель из traceparent
+        assert_eq!(root.name, "POST /v1/execute");
+        assert_eq!(root.span_kind, SpanKind::Server);
+        assert_eq!(
+            root.span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(root.parent_span_id.to_string(), "00f067aa0ba902b7");
+        assert_eq!(root.status, Status::error(""));
+        // Ok. Шаг расчета в той же трассировке, ошибка шага в статусе спана
+        assert_eq!(step.name, "rate_calc");
+        assert_eq!(step.span_context.trace_id(), root.span_context.trace_id());
+        assert_eq!(step.parent_span_id, root.span_context.span_id());
+        assert!(step.attributes.contains(&KeyValue::new("rate", 10_f64)));
+        assert_eq!(step.status, Status::error("\"invalid\""));
+
+        // Ok. Трассировка не экспортируется, если вызывающий сервис ее не сэмплирует
+        memory.0.lock().unwrap().clear();
+        with_default(Registry::default().with(layer(&provider)), || {
+            let root = info_span!("request");
+            root.set_parent(context(
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00",
+            ));
+            root.in_scope(|| debug_span!("rate_calc").in_scope(|| {}));
+        });
+        assert!(memory.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export() {
+        // коллектор: принимает один запрос и отвечает 200
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let collector = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request: Vec<u8> = Vec::new();
+            let mut buf: [u8; 4096] = [0; 4096];
+            // чтение до конца тела по Content-Length
+            loop {
+                let n: usize = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text: String = String::from_utf8_lossy(&request).to_lowercase();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length: usize = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            request
+        });
+
+        let (layer, exporter) = otlp(&format!("http://{address}"), "mg-test").unwrap();
+        with_default(Registry::default().with(layer), || {
+            info_span!("request").in_scope(|| {});
+        });
+        exporter.flush().await;
+
+        let request: Vec<u8> = collector.await.unwrap();
+        let text: String = String::from_utf8_lossy(&request).to_string();
+        let (head, body) = text.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("POST /v1/traces HTTP/1.1"), "{head}");
+        assert!(head
+            .to_lowercase()
+            .contains("content-type: application/x-protobuf"));
+        // protobuf `ExportTraceServiceRequest`: имя сервиса и спана в строковых полях
+        assert!(body.contains("mg-test"));
+        assert!(body.contains("request"));
+    }
+}
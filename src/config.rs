@@ -1,13 +1,746 @@
-use clap::Parser;
-use std::net::IpAddr;
+use crate::adapter::cache::MAX_BATCH_SIZE;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::{env, fs, io};
+use thiserror::Error;
+use toml_edit::{DocumentMut, Item};
+use tracing_subscriber::EnvFilter;
 
-/// Cli-конфиг сервиса.
-#[derive(Parser, Debug)]
+// Префикс переменных окружения конфигурации.
+const ENV_PREFIX: &str = "MG_";
+// Переменная окружения с путем к файлу конфигурации.
+const ENV_CONFIG: &str = "MG_CONFIG";
+// Стандартные переменные OpenTelemetry, используются, если не задан аналог `MG_*`.
+const OTEL_ENV: [(&str, &str); 2] = [
+    ("OTEL_EXPORTER_OTLP_ENDPOINT", "MG_TELEMETRY_OTLP_ENDPOINT"),
+    ("OTEL_SERVICE_NAME", "MG_TELEMETRY_SERVICE_NAME"),
+];
+// Необязательные строковые параметры: значение переменной окружения не разбирается как JSON.
+const OPTIONAL_STRINGS: [&str; 3] = [
+    "telemetry.otlp_endpoint",
+    "catalog.path",
+    "catalog.holidays",
+];
+
+/// Cli-конфиг сервиса: параметры запуска перекрывают файл конфигурации и переменные окружения.
+#[derive(Parser, Debug, Default)]
 pub struct Cli {
+    // файл конфигурации TOML
+    #[arg(long, env = ENV_CONFIG)]
+    pub config: Option<PathBuf>,
     // app host
-    #[arg(long, default_value = "127.0.0.1")]
-    pub host: IpAddr,
+    #[arg(long)]
+    pub host: Option<IpAddr>,
     // app port
-    #[arg(long, default_value = "8080")]
+    #[arg(long)]
+    pub port: Option<u16>,
+    // максимальный размер пакета расчетов
+    #[arg(long)]
+    pub max_batch_size: Option<usize>,
+    // файл каталога программ в формате JSON
+    #[arg(long)]
+    pub catalog: Option<PathBuf>,
+    // файл производственного календаря с праздничными днями
+    #[arg(long)]
+    pub holidays: Option<PathBuf>,
+    // задержка завершения после сигнала, сек.: /readyz уже не готов, трафик уходит с инстанса
+    #[arg(long)]
+    pub shutdown_delay: Option<u64>,
+    // формат логов
+    #[arg(long, value_enum)]
+    pub log_format: Option<LogFormat>,
+    // фильтр уровня логов: уровень или директивы `tracing` (`info,mg=debug`)
+    #[arg(long)]
+    pub log_level: Option<String>,
+    // базовый адрес коллектора OTLP/HTTP для экспорта трассировки, например `http://localhost:4318`
+    #[arg(long)]
+    pub otlp_endpoint: Option<String>,
+    // имя сервиса в трассировке
+    #[arg(long)]
+    pub service_name: Option<String>,
+}
+
+/// Формат логов.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    // компактный текст
+    #[default]
+    Text,
+    // JSON-объект в строке
+    Json,
+}
+
+/// Конфигурация сервиса.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub http: HttpConfig,
+    pub log: LogConfig,
+    pub telemetry: TelemetryConfig,
+    pub cache: CacheConfig,
+    pub catalog: CatalogConfig,
+    pub admin: AdminConfig,
+}
+
+/// Адрес и завершение работы сервиса.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: IpAddr,
     pub port: u16,
+    // задержка завершения после сигнала, сек.
+    pub shutdown_delay_secs: u64,
+}
+
+/// Ограничения http-запросов.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    // таймаут обработки запроса, сек., 0 - без таймаута
+    pub request_timeout_secs: u64,
+    // максимальный размер тела запроса, байт
+    pub body_limit_bytes: usize,
+    pub cors: CorsConfig,
+}
+
+/// Настройки CORS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    // разрешенные источники: `*` или `scheme://host[:port]`, пустой список - CORS выключен
+    pub allowed_origins: Vec<String>,
+    // время кэширования preflight-ответа браузером, сек.
+    pub max_age_secs: u64,
+}
+
+/// Логирование.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    // фильтр уровня логов: уровень или директивы `tracing`
+    pub level: String,
+}
+
+/// Экспорт трассировки.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    // базовый адрес коллектора OTLP/HTTP (http:// или https://), без адреса экспорт выключен
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+}
+
+/// Лимиты кэша и пакетных расчетов.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    // максимальное количество расчетов в кэше, без лимита - не ограничено
+    pub max_entries: Option<usize>,
+    // максимальный размер пакета расчетов
+    pub max_batch_size: usize,
+}
+
+/// Источники каталога программ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CatalogConfig {
+    // файл каталога программ в формате JSON, без файла - встроенный каталог
+    pub path: Option<PathBuf>,
+    // файл производственного календаря с праздничными днями
+    pub holidays: Option<PathBuf>,
+    // период проверки изменения файлов для перезагрузки, сек., 0 - без отслеживания
+    pub reload_interval_secs: u64,
+}
+
+/// Административные эндпоинты.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    // токен `Authorization: Bearer`, пустой - эндпоинты отключены
+    pub token: String,
+}
+
+/// Ошибки конфигурации.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    // файл конфигурации не читается
+    #[error("failed to read config file {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    // файл конфигурации не является корректным TOML
+    #[error("failed to parse config file {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    // значение не соответствует типу параметра
+    #[error(
+        "invalid configuration value for {key} from {origin}: expected {expected}, found {found}"
+    )]
+    Type {
+        key: String,
+        origin: String,
+        expected: &'static str,
+        found: String,
+    },
+    // некорректная конфигурация
+    #[error("invalid configuration: {0}")]
+    Invalid(String),
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 8080,
+            shutdown_delay_secs: 0,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout_secs: 60,
+            body_limit_bytes: 2 * 1024 * 1024,
+            cors: CorsConfig::default(),
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            max_age_secs: 600,
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            level: "info".to_string(),
+        }
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: env!("CARGO_PKG_NAME").to_string(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: None,
+            max_batch_size: MAX_BATCH_SIZE,
+        }
+    }
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            holidays: None,
+            reload_interval_secs: 10,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Источник разрешен.
+    pub fn allows(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+}
+
+impl Config {
+    /// Загрузка конфигурации по слоям: умолчания, файл TOML (`--config` или `MG_CONFIG`),
+    /// переменные окружения `MG_*`, параметры запуска.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_sources(&Cli::parse(), &env::vars().collect())
+    }
+
+    /// Переменные окружения `MG_*`, не соответствующие параметрам конфигурации, - скорее всего опечатки.
+    /// Такие переменные не влияют на конфигурацию, сервис предупреждает о них в логе.
+    pub fn unknown_env(vars: &HashMap<String, String>) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        leaves(
+            &serde_json::to_value(Config::default()).unwrap_or_default(),
+            "",
+            &mut keys,
+        );
+        let names: Vec<String> = keys.iter().map(|key| env_name(key)).collect();
+        let mut unknown: Vec<String> = vars
+            .keys()
+            .filter(|name| {
+                name.starts_with(ENV_PREFIX) && *name != ENV_CONFIG && !names.contains(name)
+            })
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown
+    }
+
+    /// Сборка конфигурации из параметров запуска и переменных окружения.
+    pub fn from_sources(cli: &Cli, vars: &HashMap<String, String>) -> Result<Self, ConfigError> {
+        let mut config: Value =
+            serde_json::to_value(Config::default()).map_err(|err| invalid(err.to_string()))?;
+        // файл конфигурации
+        if let Some(path) = &cli.config {
+            let text: String = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            let document: DocumentMut =
+                text.parse()
+                    .map_err(|err: toml_edit::TomlError| ConfigError::Parse {
+                        path: path.clone(),
+                        message: err.to_string().trim_end().to_string(),
+                    })?;
+            let file: Value = item_to_json(document.as_item());
+            merge(&mut config, file, "", &path.display().to_string())?;
+        }
+        // переменные окружения
+        env_overrides(&mut config, vars)?;
+        // параметры запуска
+        merge(&mut config, cli_overrides(cli), "", "command line")?;
+
+        let config: Config =
+            serde_json::from_value(config).map_err(|err| invalid(err.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Проверка значений, которые не проверяются типами.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        EnvFilter::try_new(&self.log.level)
+            .map_err(|err| invalid(format!("log.level {:?}: {err}", self.log.level)))?;
+        if self.cache.max_entries == Some(0) {
+            return Err(invalid("cache.max_entries must be positive".to_string()));
+        }
+        if self.cache.max_batch_size == 0 {
+            return Err(invalid("cache.max_batch_size must be positive".to_string()));
+        }
+        if self.http.body_limit_bytes == 0 {
+            return Err(invalid(
+                "http.body_limit_bytes must be positive".to_string(),
+            ));
+        }
+        for origin in &self.http.cors.allowed_origins {
+            let valid: bool = origin == "*"
+                || ((origin.starts_with("http://") || origin.starts_with("https://"))
+                    && !origin.ends_with('/')
+                    && origin.is_ascii()
+                    && !origin.chars().any(|c| c.is_ascii_control() || c == ' '));
+            if !valid {
+                return Err(invalid(format!(
+                    "http.cors.allowed_origins: {origin:?} is not `*` or `scheme://host[:port]`"
+                )));
+            }
+        }
+        if let Some(endpoint) = &self.telemetry.otlp_endpoint {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                return Err(invalid(format!(
+                    "telemetry.otlp_endpoint {endpoint:?}: expected http:// or https:// URL"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ошибка некорректной конфигурации.
+fn invalid(message: String) -> ConfigError {
+    ConfigError::Invalid(message)
+}
+
+/// Значения параметров запуска в структуре конфигурации.
+fn cli_overrides(cli: &Cli) -> Value {
+    let mut config: Value = serde_json::json!({
+        "server": {},
+        "log": {},
+        "telemetry": {},
+        "cache": {},
+        "catalog": {},
+    });
+    let mut set = |section: &str, key: &str, value: Option<Value>| {
+        if let Some(value) = value {
+            config[section][key] = value;
+        }
+    };
+    set(
+        "server",
+        "host",
+        cli.host.map(|host| host.to_string().into()),
+    );
+    set("server", "port", cli.port.map(Value::from));
+    set(
+        "server",
+        "shutdown_delay_secs",
+        cli.shutdown_delay.map(Value::from),
+    );
+    set(
+        "cache",
+        "max_batch_size",
+        cli.max_batch_size.map(Value::from),
+    );
+    set("catalog", "path", path_value(&cli.catalog));
+    set("catalog", "holidays", path_value(&cli.holidays));
+    set(
+        "log",
+        "format",
+        cli.log_format
+            .and_then(|format| serde_json::to_value(format).ok()),
+    );
+    set("log", "level", cli.log_level.clone().map(Value::from));
+    set(
+        "telemetry",
+        "otlp_endpoint",
+        cli.otlp_endpoint.clone().map(Value::from),
+    );
+    set(
+        "telemetry",
+        "service_name",
+        cli.service_name.clone().map(Value::from),
+    );
+    config
+}
+
+/// Путь в значении конфигурации.
+fn path_value(path: &Option<PathBuf>) -> Option<Value> {
+    path.as_ref()
+        .map(|path| Value::from(path.display().to_string()))
+}
+
+/// Переопределение параметров переменными окружения `MG_<SECTION>_<KEY>`,
+/// например `MG_SERVER_PORT` или `MG_HTTP_CORS_ALLOWED_ORIGINS` (список через запятую).
+/// Учитываются только переменные известных параметров, см. [`Config::unknown_env`].
+fn env_overrides(config: &mut Value, vars: &HashMap<String, String>) -> Result<(), ConfigError> {
+    let mut keys: Vec<String> = Vec::new();
+    leaves(config, "", &mut keys);
+    let mut vars: HashMap<String, String> = vars.clone();
+    for (otel, name) in OTEL_ENV {
+        if let Some(value) = vars.get(otel).cloned() {
+            vars.entry(name.to_string()).or_insert(value);
+        }
+    }
+
+    let mut names: Vec<(String, &String)> = keys.iter().map(|key| (env_name(key), key)).collect();
+    names.sort();
+    for (name, key) in names {
+        let Some(raw) = vars.get(&name) else {
+            continue;
+        };
+        let current: &Value = key.split('.').fold(&*config, |value, part| &value[part]);
+        let value: Value = match current {
+            Value::String(_) => Value::from(raw.as_str()),
+            Value::Null if OPTIONAL_STRINGS.contains(&key.as_str()) => Value::from(raw.as_str()),
+            Value::Array(_) => raw
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(Value::from)
+                .collect(),
+            // числа, флаги и необязательные числа: JSON-литерал или строка
+            _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::from(raw.as_str())),
+        };
+        let layer: Value = key.rsplit('.').fold(value, |value, part| {
+            Value::Object(Map::from_iter([(part.to_string(), value)]))
+        });
+        merge(config, layer, "", &format!("environment variable {name}"))?;
+    }
+    Ok(())
+}
+
+/// Имя переменной окружения для ключа конфигурации.
+fn env_name(key: &str) -> String {
+    format!("{ENV_PREFIX}{}", key.replace('.', "_").to_uppercase())
+}
+
+/// Пути всех параметров конфигурации.
+fn leaves(value: &Value, prefix: &str, keys: &mut Vec<String>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                leaves(field, &join(prefix, name), keys);
+            }
+        }
+        _ => keys.push(prefix.to_string()),
+    }
+}
+
+/// Путь к вложенному параметру.
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Наложение слоя на конфигурацию с проверкой имен и типов параметров.
+fn merge(config: &mut Value, layer: Value, prefix: &str, source: &str) -> Result<(), ConfigError> {
+    match (config, layer) {
+        (Value::Object(fields), Value::Object(layer)) => {
+            for (name, value) in layer {
+                let key: String = join(prefix, &name);
+                match fields.get_mut(&name) {
+                    Some(field) => merge(field, value, &key, source)?,
+                    None => {
+                        return Err(invalid(format!("unknown key {key} in {source}")));
+                    }
+                }
+            }
+            Ok(())
+        }
+        (config, layer) => {
+            let expected: Option<&'static str> = match (&*config, &layer) {
+                (Value::Object(_), Value::Object(_)) => None,
+                (Value::Object(_), _) => Some("table"),
+                (_, Value::Object(_)) if !config.is_null() => Some(kind(config)),
+                (Value::Null, _) => None,
+                (Value::Number(_), Value::Number(_))
+                | (Value::String(_), Value::String(_))
+                | (Value::Bool(_), Value::Bool(_))
+                | (Value::Array(_), Value::Array(_)) => None,
+                (config, _) => Some(kind(config)),
+            };
+            if let Some(expected) = expected {
+                return Err(ConfigError::Type {
+                    key: prefix.to_string(),
+                    origin: source.to_string(),
+                    expected,
+                    found: layer.to_string(),
+                });
+            }
+            *config = layer;
+            Ok(())
+        }
+    }
+}
+
+/// Название типа значения для сообщений об ошибках.
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "table",
+    }
+}
+
+/// Преобразование документа TOML в JSON.
+fn item_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => table
+            .iter()
+            .map(|(key, item)| (key.to_string(), item_to_json(item)))
+            .collect::<Map<String, Value>>()
+            .into(),
+        Item::ArrayOfTables(tables) => tables
+            .iter()
+            .map(|table| item_to_json(&Item::Table(table.clone())))
+            .collect(),
+    }
+}
+
+/// Преобразование значения TOML в JSON.
+fn value_to_json(value: &toml_edit::Value) -> Value {
+    use toml_edit::Value as Toml;
+    match value {
+        Toml::String(s) => Value::from(s.value().as_str()),
+        Toml::Integer(i) => Value::from(*i.value()),
+        Toml::Float(f) => Value::from(*f.value()),
+        Toml::Boolean(b) => Value::from(*b.value()),
+        Toml::Datetime(d) => Value::from(d.value().to_string()),
+        Toml::Array(array) => array.iter().map(value_to_json).collect(),
+        Toml::InlineTable(table) => table
+            .iter()
+            .map(|(key, value)| (key.to_string(), value_to_json(value)))
+            .collect::<Map<String, Value>>()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Файл конфигурации во временной директории.
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path: PathBuf = env::temp_dir().join(format!("mg-{name}-{}.toml", std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let actual: Config = Config::from_sources(&Cli::default(), &HashMap::new()).unwrap();
+        assert_eq!(actual, Config::default());
+        assert_eq!(actual.server.port, 8080);
+        assert_eq!(actual.cache.max_batch_size, MAX_BATCH_SIZE);
+    }
+
+    #[test]
+    fn test_layers() {
+        let path: PathBuf = file(
+            "layers",
+            r#"
+[server]
+port = 9000
+shutdown_delay_secs = 5
+
+[log]
+format = "json"
+level = "warn"
+
+[cache]
+max_entries = 100
+
+[http]
+body_limit_bytes = 1024
+
+[http.cors]
+allowed_origins = ["https://app.example.com"]
+"#,
+        );
+        let cli: Cli = Cli {
+            config: Some(path.clone()),
+            port: Some(9100),
+            ..Cli::default()
+        };
+        let env: HashMap<String, String> = vars(&[
+            ("MG_SERVER_PORT", "9050"),
+            ("MG_LOG_LEVEL", "debug"),
+            ("MG_CACHE_MAX_ENTRIES", "50"),
+            (
+                "MG_HTTP_CORS_ALLOWED_ORIGINS",
+                "https://a.example.com, https://b.example.com",
+            ),
+            ("MG_CATALOG_PATH", "2025"),
+            ("MG_TELEMETRY_OTLP_ENDPOINT", "http://collector:4318"),
+            ("OTEL_SERVICE_NAME", "mortgage"),
+            ("MG_SERVER_PROT", "1"),
+            ("PATH", "/usr/bin"),
+        ]);
+        let actual: Config = Config::from_sources(&cli, &env).unwrap();
+        fs::remove_file(path).unwrap();
+        // Ok. Параметр запуска перекрывает переменную окружения и файл
+        assert_eq!(actual.server.port, 9100);
+        // Ok. Переменная окружения перекрывает файл
+        assert_eq!(actual.log.level, "debug");
+        assert_eq!(actual.cache.max_entries, Some(50));
+        assert_eq!(
+            actual.http.cors.allowed_origins,
+            vec!["https://a.example.com", "https://b.example.com"]
+        );
+        assert_eq!(actual.telemetry.service_name, "mortgage");
+        // Ok. Необязательные строковые параметры не разбираются как JSON
+        assert_eq!(actual.catalog.path, Some(PathBuf::from("2025")));
+        assert_eq!(
+            actual.telemetry.otlp_endpoint.as_deref(),
+            Some("http://collector:4318")
+        );
+        // Ok. Неизвестная переменная окружения не влияет на конфигурацию
+        assert_eq!(Config::unknown_env(&env), vec!["MG_SERVER_PROT"]);
+        // Ok. Значения из файла
+        assert_eq!(actual.server.shutdown_delay_secs, 5);
+        assert_eq!(actual.log.format, LogFormat::Json);
+        assert_eq!(actual.http.body_limit_bytes, 1024);
+        // Ok. Умолчания
+        assert_eq!(actual.http.request_timeout_secs, 60);
+    }
+
+    #[test]
+    fn test_optional_strings() {
+        // необязательные строковые параметры существуют и по умолчанию не заданы
+        let config: Value = serde_json::to_value(Config::default()).unwrap();
+        for key in OPTIONAL_STRINGS {
+            let pointer: String = format!("/{}", key.replace('.', "/"));
+            assert_eq!(config.pointer(&pointer), Some(&Value::Null), "{key}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let load = |content: &str, env: &[(&str, &str)]| -> String {
+            let path: PathBuf = file("errors", content);
+            let cli: Cli = Cli {
+                config: Some(path.clone()),
+                ..Cli::default()
+            };
+            let err: ConfigError = Config::from_sources(&cli, &vars(env)).err().unwrap();
+            fs::remove_file(path).unwrap();
+            err.to_string()
+        };
+        // Err. Некорректный TOML
+        assert!(load("[server\nport = 1", &[]).starts_with("failed to parse config file"));
+        // Err. Неизвестный параметр
+        assert!(load("[server]\nprot = 1", &[]).contains("unknown key server.prot"));
+        // Err. Тип параметра
+        let actual: String = load("[server]\nport = \"http\"", &[]);
+        assert!(actual.contains("server.port"), "{actual}");
+        assert!(actual.contains("expected number"), "{actual}");
+        // Err. Тип параметра из переменной окружения
+        let actual: String = load("", &[("MG_SERVER_PORT", "http")]);
+        assert!(
+            actual.contains("environment variable MG_SERVER_PORT"),
+            "{actual}"
+        );
+        // Err. Некорректные значения
+        assert!(load("[log]\nlevel = \"info,=\"", &[]).contains("log.level"));
+        assert!(load("[cache]\nmax_entries = 0", &[]).contains("cache.max_entries"));
+        assert!(
+            load("[http.cors]\nallowed_origins = [\"example.com\"]", &[])
+                .contains("http.cors.allowed_origins")
+        );
+        assert!(load("[telemetry]\notlp_endpoint = \"collector:4317\"", &[])
+            .contains("telemetry.otlp_endpoint"));
+        // Err. Нет файла
+        let cli: Cli = Cli {
+            config: Some(PathBuf::from("/nonexistent/mg.toml")),
+            ..Cli::default()
+        };
+        let actual: ConfigError = Config::from_sources(&cli, &HashMap::new()).err().unwrap();
+        assert!(matches!(actual, ConfigError::Read { .. }));
+    }
+
+    #[test]
+    fn test_cors_allows() {
+        let cors: CorsConfig = CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..CorsConfig::default()
+        };
+        assert!(cors.allows("https://app.example.com"));
+        assert!(!cors.allows("https://evil.example.com"));
+        let any: CorsConfig = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            ..CorsConfig::default()
+        };
+        assert!(any.allows("https://evil.example.com"));
+    }
 }
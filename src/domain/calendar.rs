@@ -0,0 +1,185 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+use utoipa::ToSchema;
+
+/// Правило переноса даты платежа с нерабочего дня.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DateShift {
+    // без переноса
+    #[default]
+    None,
+    // на следующий рабочий день
+    Following,
+    // на следующий рабочий день, если он в том же месяце, иначе - на предыдущий
+    ModifiedFollowing,
+}
+
+/// Производственный календарь: выходные и праздничные дни.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Calendar {
+    pub holidays: BTreeSet<NaiveDate>,
+    // рабочие выходные дни (перенесенные рабочие субботы)
+    pub working_days: BTreeSet<NaiveDate>,
+}
+
+impl Calendar {
+    /// Разбор календаря: по одной дате `YYYY-MM-DD` в строке, дата с `+` - рабочий выходной день,
+    /// пустые строки и комментарии после `#` пропускаются.
+    pub fn parse(content: &str) -> Result<Self, chrono::ParseError> {
+        let mut calendar: Calendar = Calendar::default();
+        for line in content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty())
+        {
+            match line.strip_prefix('+') {
+                Some(day) => calendar
+                    .working_days
+                    .insert(NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d")?),
+                None => calendar
+                    .holidays
+                    .insert(NaiveDate::parse_from_str(line, "%Y-%m-%d")?),
+            };
+        }
+
+        Ok(calendar)
+    }
+
+    /// Загрузка календаря из файла.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Рабочий день: рабочий выходной либо не суббота, не воскресенье и не праздник.
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date)
+            || !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                && !self.holidays.contains(&date)
+    }
+
+    /// Перенос даты с нерабочего дня по правилу.
+    pub fn adjust(&self, date: NaiveDate, shift: DateShift) -> NaiveDate {
+        match shift {
+            DateShift::None => date,
+            DateShift::Following => self.following(date),
+            DateShift::ModifiedFollowing => {
+                let following: NaiveDate = self.following(date);
+                if following.month() == date.month() {
+                    following
+                } else {
+                    self.preceding(date)
+                }
+            }
+        }
+    }
+
+    /// Ближайший рабочий день не раньше даты.
+    fn following(&self, mut date: NaiveDate) -> NaiveDate {
+        while !self.is_business_day(date) {
+            match date.succ_opt() {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+        date
+    }
+
+    /// Ближайший рабочий день не позже даты.
+    fn preceding(&self, mut date: NaiveDate) -> NaiveDate {
+        while !self.is_business_day(date) {
+            match date.pred_opt() {
+                Some(prev) => date = prev,
+                None => break,
+            }
+        }
+        date
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        // Ok. Даты с комментариями и пустыми строками
+        let actual: Calendar =
+            Calendar::parse("# новогодние\n2025-01-01\n\n2025-01-02 # четверг\n").unwrap();
+        assert_eq!(
+            actual.holidays,
+            BTreeSet::from([date(2025, 1, 1), date(2025, 1, 2)])
+        );
+        assert!(actual.working_days.is_empty());
+        // Ok. Рабочая суббота
+        let actual: Calendar = Calendar::parse("2025-11-03\n+2025-11-01 # перенос\n").unwrap();
+        assert_eq!(actual.holidays, BTreeSet::from([date(2025, 11, 3)]));
+        assert_eq!(actual.working_days, BTreeSet::from([date(2025, 11, 1)]));
+        // Err. Некорректная дата
+        assert!(Calendar::parse("2025-13-01").is_err());
+        assert!(Calendar::parse("+2025-11-31").is_err());
+    }
+
+    #[test]
+    fn test_is_business_day() {
+        let calendar: Calendar = Calendar::parse("2025-06-12").unwrap();
+        // Ok. Пятница
+        assert!(calendar.is_business_day(date(2025, 6, 13)));
+        // Ok. Суббота
+        assert!(!calendar.is_business_day(date(2025, 6, 14)));
+        // Ok. Праздник
+        assert!(!calendar.is_business_day(date(2025, 6, 12)));
+        // Ok. Рабочая суббота
+        let calendar: Calendar = Calendar::parse("+2025-11-01").unwrap();
+        assert!(calendar.is_business_day(date(2025, 11, 1)));
+        assert!(!calendar.is_business_day(date(2025, 11, 2)));
+    }
+
+    #[test]
+    fn test_adjust() {
+        let calendar: Calendar = Calendar::parse("2025-06-12\n2025-06-13").unwrap();
+        // Ok. Без переноса
+        assert_eq!(
+            calendar.adjust(date(2025, 6, 12), DateShift::None),
+            date(2025, 6, 12)
+        );
+        // Ok. Праздники и выходные - на понедельник
+        assert_eq!(
+            calendar.adjust(date(2025, 6, 12), DateShift::Following),
+            date(2025, 6, 16)
+        );
+        // Ok. Рабочий день не переносится
+        assert_eq!(
+            calendar.adjust(date(2025, 6, 16), DateShift::ModifiedFollowing),
+            date(2025, 6, 16)
+        );
+        // Ok. Суббота 31 мая - на следующий рабочий день 2 июня
+        assert_eq!(
+            calendar.adjust(date(2025, 5, 31), DateShift::Following),
+            date(2025, 6, 2)
+        );
+        // Ok. Суббота 31 мая - в пределах месяца на пятницу 30 мая
+        assert_eq!(
+            calendar.adjust(date(2025, 5, 31), DateShift::ModifiedFollowing),
+            date(2025, 5, 30)
+        );
+        // Ok. Рабочая суббота 1 ноября не переносится, воскресенье 2 ноября - после праздников на 5 ноября
+        let calendar: Calendar = Calendar::parse("2025-11-03\n2025-11-04\n+2025-11-01").unwrap();
+        assert_eq!(
+            calendar.adjust(date(2025, 11, 1), DateShift::Following),
+            date(2025, 11, 1)
+        );
+        assert_eq!(
+            calendar.adjust(date(2025, 11, 2), DateShift::Following),
+            date(2025, 11, 5)
+        );
+    }
+}
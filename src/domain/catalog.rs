@@ -0,0 +1,737 @@
+use crate::domain::calendar::Calendar;
+use crate::domain::mortgage::{Borrower, ProgramKind};
+use crate::errors::AppError;
+use crate::errors::AppError::{
+    BorrowerAge, MilitaryServiceRequired, NotEnoughChildren, OptionUnavailable, ProgramUnavailable,
+    RegionUnavailable, SalaryClientRequired,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+// Годовая процентаня ставка зарплатника.
+pub const SALARY: f64 = 8_f64;
+// Годовая процентаня ставка военного.
+pub const MILITARY: f64 = 9_f64;
+// Годовая базовая процентаня ставка.
+pub const BASE: f64 = 10_f64;
+// Годовая процентаня ставка семейной ипотеки.
+pub const FAMILY: f64 = 6_f64;
+// Лимит суммы кредита семейной ипотеки.
+const FAMILY_MAX_LOAN: f64 = 6_000_000_f64;
+// Лимит суммы кредита семейной ипотеки в Москве и Санкт-Петербурге.
+const FAMILY_CAPITAL_MAX_LOAN: f64 = 12_000_000_f64;
+// Максимальный возраст заемщика на дату погашения кредита.
+const MAX_AGE_AT_MATURITY: u8 = 75;
+// Максимальная доля платежа в совокупном доходе, %.
+const MAX_PAYMENT_TO_INCOME: f64 = 50_f64;
+// Максимальный срок кредита, мес.
+pub const MAX_MONTHS: u16 = 360;
+// Версия встроенного каталога.
+pub const BUILTIN_VERSION: &str = "builtin";
+
+/// Источник первоначального взноса.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    OwnFunds,
+    MaternityCapital,
+    StateSubsidy,
+    Employer,
+}
+
+/// Ошибки проверки каталога программ.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CatalogError {
+    // ставка программы не положительная
+    #[error("program {0:?}: rate must be positive")]
+    Rate(ProgramKind),
+    // диапазоны надбавок не упорядочены по возрастанию или пересекаются
+    #[error("program {program:?}: {factor:?} bands must be ordered and must not overlap")]
+    Bands {
+        program: ProgramKind,
+        factor: RateFactor,
+    },
+    // минимальная сумма кредита больше максимальной
+    #[error("program {0:?}: min_loan must not exceed max_loan")]
+    LoanLimits(ProgramKind),
+}
+
+/// Правила программы кредитования.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramRules {
+    // годовая процентная ставка программы
+    pub rate: f64,
+    // надбавки к ставке по LTV, %
+    #[serde(default)]
+    pub ltv_bands: Vec<RateBand>,
+    // надбавки к ставке по сроку, мес.
+    #[serde(default)]
+    pub term_bands: Vec<RateBand>,
+    // надбавки к ставке по дополнительным опциям, п.п.
+    #[serde(default)]
+    pub options: BTreeMap<RateOption, f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_loan: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_loan: Option<f64>,
+    // лимиты суммы кредита по регионам, приоритетнее общего
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regional_limits: Vec<RegionalLimit>,
+    // программа для части кредита сверх лимита при комбинированном расчете
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overflow_program: Option<ProgramKind>,
+    // источники, учитываемые в минимальном первоначальном взносе
+    pub counted_sources: Vec<Source>,
+    // условия программы для заемщика
+    #[serde(default)]
+    pub eligibility: Eligibility,
+}
+
+/// Лимит суммы кредита в регионах.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionalLimit {
+    pub regions: Vec<String>,
+    pub max_loan: f64,
+}
+
+/// Условия программы для заемщика.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Eligibility {
+    #[serde(default)]
+    pub military_service: bool,
+    #[serde(default)]
+    pub salary_client: bool,
+    #[serde(default)]
+    pub min_children: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u8>,
+    // допустимые регионы, пустой список - все регионы
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+}
+
+/// Надбавка (+) или скидка (-) к ставке в п.п. для значений в диапазоне (over, up_to].
+/// Отсутствующая граница диапазона не ограничивает.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateBand {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub over: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub up_to: Option<f64>,
+    pub adjustment: f64,
+}
+
+/// Дополнительная опция, изменяющая ставку.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateOption {
+    // отказ от страхования жизни
+    NoLifeInsurance,
+    // электронная регистрация сделки
+    ElectronicRegistration,
+    // получение зарплаты на карту банка
+    SalaryCard,
+}
+
+/// Фактор изменения ставки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateFactor {
+    Ltv,
+    Term,
+    NoLifeInsurance,
+    ElectronicRegistration,
+    SalaryCard,
+}
+
+impl From<RateOption> for RateFactor {
+    fn from(option: RateOption) -> Self {
+        match option {
+            RateOption::NoLifeInsurance => RateFactor::NoLifeInsurance,
+            RateOption::ElectronicRegistration => RateFactor::ElectronicRegistration,
+            RateOption::SalaryCard => RateFactor::SalaryCard,
+        }
+    }
+}
+
+/// Примененное изменение ставки, п.п.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RateAdjustment {
+    pub factor: RateFactor,
+    pub value: f64,
+}
+
+/// Состав ставки: ставка программы и примененные изменения.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RateBreakdown {
+    pub base_rate: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub adjustments: Vec<RateAdjustment>,
+}
+
+/// Каталог программ кредитования.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Catalog {
+    // версия каталога
+    #[serde(default = "builtin_version")]
+    pub version: String,
+    pub programs: BTreeMap<ProgramKind, ProgramRules>,
+    pub max_age_at_maturity: u8,
+    pub max_payment_to_income: f64,
+    // производственный календарь для переноса дат платежей
+    #[serde(skip)]
+    pub calendar: Arc<Calendar>,
+}
+
+impl Default for Catalog {
+    /// Встроенный каталог программ.
+    fn default() -> Self {
+        Self {
+            version: builtin_version(),
+            programs: BTreeMap::from([
+                (
+                    ProgramKind::Salary,
+                    ProgramRules {
+                        rate: SALARY,
+                        ltv_bands: ltv_bands(),
+                        term_bands: Vec::new(),
+                        options: options(),
+                        min_loan: None,
+                        max_loan: None,
+                        regional_limits: Vec::new(),
+                        overflow_program: None,
+                        counted_sources: vec![
+                            Source::OwnFunds,
+                            Source::MaternityCapital,
+                            Source::Employer,
+                        ],
+                        eligibility: Eligibility {
+                            salary_client: true,
+                            ..Eligibility::default()
+                        },
+                    },
+                ),
+                (
+                    ProgramKind::Military,
+                    ProgramRules {
+                        rate: MILITARY,
+                        ltv_bands: ltv_bands(),
+                        term_bands: Vec::new(),
+                        options: options(),
+                        min_loan: None,
+                        max_loan: None,
+                        regional_limits: Vec::new(),
+                        overflow_program: None,
+                        counted_sources: vec![
+                            Source::OwnFunds,
+                            Source::MaternityCapital,
+                            Source::StateSubsidy,
+                        ],
+                        eligibility: Eligibility {
+                            military_service: true,
+                            ..Eligibility::default()
+                        },
+                    },
+                ),
+                (
+                    ProgramKind::Base,
+                    ProgramRules {
+                        rate: BASE,
+                        ltv_bands: ltv_bands(),
+                        term_bands: Vec::new(),
+                        options: options(),
+                        min_loan: None,
+                        max_loan: None,
+                        regional_limits: Vec::new(),
+                        overflow_program: None,
+                        counted_sources: vec![
+                            Source::OwnFunds,
+                            Source::MaternityCapital,
+                            Source::StateSubsidy,
+                            Source::Employer,
+                        ],
+                        eligibility: Eligibility::default(),
+                    },
+                ),
+                (
+                    ProgramKind::Family,
+                    ProgramRules {
+                        rate: FAMILY,
+                        ltv_bands: ltv_bands(),
+                        term_bands: Vec::new(),
+                        options: options(),
+                        min_loan: None,
+                        max_loan: Some(FAMILY_MAX_LOAN),
+                        regional_limits: vec![RegionalLimit {
+                            regions: vec!["77".to_string(), "78".to_string()],
+                            max_loan: FAMILY_CAPITAL_MAX_LOAN,
+                        }],
+                        overflow_program: Some(ProgramKind::Base),
+                        counted_sources: vec![
+                            Source::OwnFunds,
+                            Source::MaternityCapital,
+                            Source::StateSubsidy,
+                            Source::Employer,
+                        ],
+                        eligibility: Eligibility {
+                            min_children: 1,
+                            ..Eligibility::default()
+                        },
+                    },
+                ),
+            ]),
+            max_age_at_maturity: MAX_AGE_AT_MATURITY,
+            max_payment_to_income: MAX_PAYMENT_TO_INCOME,
+            calendar: Arc::default(),
+        }
+    }
+}
+
+/// Версия встроенного каталога.
+fn builtin_version() -> String {
+    BUILTIN_VERSION.to_string()
+}
+
+/// Встроенные надбавки по LTV: -0.3 п.п. до 50% и +0.5 п.п. выше 80%.
+fn ltv_bands() -> Vec<RateBand> {
+    vec![
+        RateBand {
+            over: None,
+            up_to: Some(50_f64),
+            adjustment: -0.3,
+        },
+        RateBand {
+            over: Some(80_f64),
+            up_to: None,
+            adjustment: 0.5,
+        },
+    ]
+}
+
+/// Встроенные надбавки по опциям: +1 п.п. за отказ от страхования жизни,
+/// -0.1 п.п. за электронную регистрацию и зарплатную карту.
+fn options() -> BTreeMap<RateOption, f64> {
+    BTreeMap::from([
+        (RateOption::NoLifeInsurance, 1_f64),
+        (RateOption::ElectronicRegistration, -0.1),
+        (RateOption::SalaryCard, -0.1),
+    ])
+}
+
+impl Catalog {
+    /// Загрузка каталога из JSON-файла. Каталог без версии получает версию `builtin`,
+    /// поэтому в файле ее стоит задавать явно.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Правила выбранной программы.
+    pub fn rules(&self, kind: ProgramKind) -> Result<&ProgramRules, AppError> {
+        self.programs.get(&kind).ok_or(ProgramUnavailable)
+    }
+
+    /// Проверка правил программ: положительная ставка, упорядоченные непересекающиеся
+    /// диапазоны надбавок, минимальная сумма кредита не больше максимальной.
+    pub fn validate(&self) -> Result<(), CatalogError> {
+        for (&program, rules) in &self.programs {
+            if rules.rate <= 0_f64 {
+                return Err(CatalogError::Rate(program));
+            }
+            for (factor, bands) in [
+                (RateFactor::Ltv, &rules.ltv_bands),
+                (RateFactor::Term, &rules.term_bands),
+            ] {
+                if !RateBand::ordered(bands) {
+                    return Err(CatalogError::Bands { program, factor });
+                }
+            }
+            if let Some(min_loan) = rules.min_loan {
+                let mut max_loans = rules
+                    .regional_limits
+                    .iter()
+                    .map(|limit| limit.max_loan)
+                    .chain(rules.max_loan);
+                if max_loans.any(|max_loan| min_loan > max_loan) {
+                    return Err(CatalogError::LoanLimits(program));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RateBand {
+    /// Попадание значения в диапазон.
+    pub fn contains(&self, value: f64) -> bool {
+        self.over.is_none_or(|over| value > over) && self.up_to.is_none_or(|up_to| value <= up_to)
+    }
+
+    /// Диапазоны не пусты, упорядочены по возрастанию и не пересекаются:
+    /// неограниченным снизу может быть только первый, сверху - только последний.
+    fn ordered(bands: &[RateBand]) -> bool {
+        let nonempty: bool = bands.iter().all(|band| match (band.over, band.up_to) {
+            (Some(over), Some(up_to)) => over < up_to,
+            _ => true,
+        });
+        nonempty
+            && bands
+                .windows(2)
+                .all(|pair| match (pair[0].up_to, pair[1].over) {
+                    (Some(up_to), Some(over)) => up_to <= over,
+                    _ => false,
+                })
+    }
+}
+
+impl ProgramRules {
+    /// Максимальная сумма кредита в регионе.
+    pub fn max_loan(&self, region: Option<&String>) -> Option<f64> {
+        region
+            .and_then(|region| {
+                self.regional_limits
+                    .iter()
+                    .find(|limit| limit.regions.contains(region))
+            })
+            .map(|limit| limit.max_loan)
+            .or(self.max_loan)
+    }
+
+    /// Надбавки к ставке по первым подходящим диапазонам LTV и срока
+    /// и по выбранным опциям. Повторно указанная опция применяется один раз.
+    pub fn rate_adjustments(
+        &self,
+        ltv: f64,
+        months: u16,
+        options: &[RateOption],
+    ) -> Result<Vec<RateAdjustment>, AppError> {
+        let mut adjustments: Vec<RateAdjustment> = [
+            (RateFactor::Ltv, &self.ltv_bands, ltv),
+            (RateFactor::Term, &self.term_bands, months as f64),
+        ]
+        .into_iter()
+        .filter_map(|(factor, bands, value)| {
+            bands
+                .iter()
+                .find(|band| band.contains(value))
+                .map(|band| RateAdjustment {
+                    factor,
+                    value: band.adjustment,
+                })
+        })
+        .collect();
+
+        for (i, option) in options.iter().enumerate() {
+            if options[..i].contains(option) {
+                continue;
+            }
+            let value: f64 = *self.options.get(option).ok_or(OptionUnavailable)?;
+            adjustments.push(RateAdjustment {
+                factor: (*option).into(),
+                value,
+            });
+        }
+
+        Ok(adjustments)
+    }
+}
+
+impl Eligibility {
+    /// Проверка заемщика на соответствие условиям программы. Условия применяются
+    /// к основному заемщику, созаемщики не проверяются.
+    /// Возраст на дату выдачи обязателен, если программа его ограничивает.
+    #[instrument(name = "eligibility_check", level = "debug", skip_all)]
+    pub fn check(&self, borrower: &Borrower, issue_date: NaiveDate) -> Result<(), AppError> {
+        if self.military_service && !borrower.military_service {
+            return Err(MilitaryServiceRequired);
+        }
+        if self.salary_client && !borrower.salary_client {
+            return Err(SalaryClientRequired);
+        }
+        if borrower.children < self.min_children {
+            return Err(NotEnoughChildren);
+        }
+        if self.min_age.is_some() || self.max_age.is_some() {
+            let age: u8 = borrower.age_at(issue_date).ok_or(BorrowerAge)?;
+            if self.min_age.is_some_and(|min| age < min)
+                || self.max_age.is_some_and(|max| age > max)
+            {
+                return Err(BorrowerAge);
+            }
+        }
+        if !self.regions.is_empty()
+            && !borrower
+                .region
+                .as_ref()
+                .is_some_and(|region| self.regions.contains(region))
+        {
+            return Err(RegionUnavailable);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let catalog: Catalog = Catalog::default();
+        // Ok. Программа есть в каталоге
+        let actual: &ProgramRules = catalog.rules(ProgramKind::Salary).unwrap();
+        assert!(!actual.counted_sources.contains(&Source::StateSubsidy));
+        // Err. Программы нет в каталоге
+        let mut catalog: Catalog = Catalog::default();
+        catalog.programs.remove(&ProgramKind::Base);
+        assert_eq!(
+            catalog.rules(ProgramKind::Base).err().unwrap(),
+            ProgramUnavailable
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("catalog-{}.json", std::process::id()));
+        let mut expected: Catalog = Catalog {
+            version: "2025-06".to_string(),
+            ..Catalog::default()
+        };
+        expected.programs.remove(&ProgramKind::Military);
+        std::fs::write(&path, serde_json::to_vec(&expected).unwrap()).unwrap();
+        // Ok. Каталог из файла
+        let actual: Catalog = Catalog::from_file(&path).unwrap();
+        assert_eq!(actual, expected);
+        // Err. Некорректный JSON
+        std::fs::write(&path, "{\"programs\": []}").unwrap();
+        let err: io::Error = Catalog::from_file(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+        // Err. Нет файла
+        assert!(Catalog::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_rate_adjustments() {
+        let mut rules: ProgramRules = Catalog::default().rules(ProgramKind::Base).unwrap().clone();
+        // Ok. LTV 80% - без надбавки
+        assert!(rules.rate_adjustments(80_f64, 240, &[]).unwrap().is_empty());
+        // Ok. Скидка по LTV и надбавка по сроку
+        rules.term_bands = vec![RateBand {
+            over: Some(180_f64),
+            up_to: None,
+            adjustment: 0.2,
+        }];
+        assert_eq!(
+            rules.rate_adjustments(50_f64, 240, &[]).unwrap(),
+            vec![
+                RateAdjustment {
+                    factor: RateFactor::Ltv,
+                    value: -0.3
+                },
+                RateAdjustment {
+                    factor: RateFactor::Term,
+                    value: 0.2
+                },
+            ]
+        );
+        // Ok. Срок вне диапазона
+        assert_eq!(rules.rate_adjustments(85_f64, 180, &[]).unwrap().len(), 1);
+        // Ok. Опции применяются в порядке запроса, повтор игнорируется
+        let options: [RateOption; 3] = [
+            RateOption::NoLifeInsurance,
+            RateOption::SalaryCard,
+            RateOption::NoLifeInsurance,
+        ];
+        assert_eq!(
+            rules.rate_adjustments(70_f64, 180, &options).unwrap(),
+            vec![
+                RateAdjustment {
+                    factor: RateFactor::NoLifeInsurance,
+                    value: 1_f64
+                },
+                RateAdjustment {
+                    factor: RateFactor::SalaryCard,
+                    value: -0.1
+                },
+            ]
+        );
+        // Err. Опция недоступна в программе
+        rules.options.remove(&RateOption::SalaryCard);
+        assert_eq!(
+            rules.rate_adjustments(70_f64, 180, &options).err().unwrap(),
+            OptionUnavailable
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        // Ok. Встроенный каталог
+        assert_eq!(Catalog::default().validate(), Ok(()));
+        // Err. Ставка не положительная
+        let mut catalog: Catalog = Catalog::default();
+        catalog.programs.get_mut(&ProgramKind::Base).unwrap().rate = 0_f64;
+        assert_eq!(
+            catalog.validate(),
+            Err(CatalogError::Rate(ProgramKind::Base))
+        );
+        // Err. Диапазоны не упорядочены
+        let mut catalog: Catalog = Catalog::default();
+        catalog
+            .programs
+            .get_mut(&ProgramKind::Base)
+            .unwrap()
+            .ltv_bands
+            .reverse();
+        assert_eq!(
+            catalog.validate(),
+            Err(CatalogError::Bands {
+                program: ProgramKind::Base,
+                factor: RateFactor::Ltv
+            })
+        );
+        // Err. Диапазоны пересекаются
+        let mut catalog: Catalog = Catalog::default();
+        catalog
+            .programs
+            .get_mut(&ProgramKind::Base)
+            .unwrap()
+            .term_bands = vec![
+            RateBand {
+                over: Some(120_f64),
+                up_to: Some(240_f64),
+                adjustment: 0.1,
+            },
+            RateBand {
+                over: Some(180_f64),
+                up_to: None,
+                adjustment: 0.2,
+            },
+        ];
+        assert_eq!(
+            catalog.validate(),
+            Err(CatalogError::Bands {
+                program: ProgramKind::Base,
+                factor: RateFactor::Term
+            })
+        );
+        // Err. Пустой диапазон
+        let mut catalog: Catalog = Catalog::default();
+        catalog
+            .programs
+            .get_mut(&ProgramKind::Base)
+            .unwrap()
+            .term_bands = vec![RateBand {
+            over: Some(240_f64),
+            up_to: Some(120_f64),
+            adjustment: 0.1,
+        }];
+        assert!(catalog.validate().is_err());
+        // Err. Минимальная сумма больше максимальной, в том числе региональной
+        // 7 млн больше общего лимита 6 млн
+        let mut catalog: Catalog = Catalog::default();
+        catalog
+            .programs
+            .get_mut(&ProgramKind::Family)
+            .unwrap()
+            .min_loan = Some(5_000_000_f64);
+        assert_eq!(catalog.validate(), Ok(()));
+        catalog
+            .programs
+            .get_mut(&ProgramKind::Family)
+            .unwrap()
+            .min_loan = Some(7_000_000_f64);
+        assert_eq!(
+            catalog.validate(),
+            Err(CatalogError::LoanLimits(ProgramKind::Family))
+        );
+    }
+
+    #[test]
+    fn test_max_loan() {
+        let catalog: Catalog = Catalog::default();
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Family).unwrap();
+        assert_eq!(rules.max_loan(None), Some(FAMILY_MAX_LOAN));
+        assert_eq!(
+            rules.max_loan(Some(&"50".to_string())),
+            Some(FAMILY_MAX_LOAN)
+        );
+        assert_eq!(
+            rules.max_loan(Some(&"78".to_string())),
+            Some(FAMILY_CAPITAL_MAX_LOAN)
+        );
+        // Ok. Программа без лимита
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Base).unwrap();
+        assert_eq!(rules.max_loan(Some(&"77".to_string())), None);
+    }
+
+    #[test]
+    fn test_eligibility_check() {
+        let catalog: Catalog = Catalog::default();
+        let mut borrower: Borrower = Borrower::default();
+        let date: NaiveDate = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        // Ok. Базовая программа без условий
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Base).unwrap();
+        assert!(rules.eligibility.check(&borrower, date).is_ok());
+        // Err. Зарплатная программа только для клиентов зарплатного проекта
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Salary).unwrap();
+        assert_eq!(
+            rules.eligibility.check(&borrower, date).err().unwrap(),
+            SalaryClientRequired
+        );
+        // Err. Семейная ипотека только для заемщиков с детьми
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Family).unwrap();
+        assert_eq!(
+            rules.eligibility.check(&borrower, date).err().unwrap(),
+            NotEnoughChildren
+        );
+        borrower.children = 2;
+        assert!(rules.eligibility.check(&borrower, date).is_ok());
+        // Err. Возраст не указан или вне диапазона
+        let eligibility: Eligibility = Eligibility {
+            min_age: Some(21),
+            max_age: Some(65),
+            regions: vec!["77".to_string()],
+            ..Eligibility::default()
+        };
+        assert_eq!(
+            eligibility.check(&borrower, date).err().unwrap(),
+            BorrowerAge
+        );
+        borrower.age = Some(18);
+        assert_eq!(
+            eligibility.check(&borrower, date).err().unwrap(),
+            BorrowerAge
+        );
+        // Err. Регион не входит в программу
+        borrower.age = Some(30);
+        borrower.region = Some("50".to_string());
+        assert_eq!(
+            eligibility.check(&borrower, date).err().unwrap(),
+            RegionUnavailable
+        );
+        // Ok. Все условия выполнены
+        borrower.region = Some("77".to_string());
+        assert!(eligibility.check(&borrower, date).is_ok());
+        // Err. Возраст по дате рождения на дату выдачи важнее указанного возраста
+        borrower.date_of_birth = NaiveDate::from_ymd_opt(2004, 6, 2);
+        assert_eq!(
+            eligibility.check(&borrower, date).err().unwrap(),
+            BorrowerAge
+        );
+        // Ok. 21 год исполнился в день выдачи
+        borrower.age = Some(18);
+        borrower.date_of_birth = NaiveDate::from_ymd_opt(2004, 6, 1);
+        assert!(eligibility.check(&borrower, date).is_ok());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::domain::schedule::Payment;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Ставка НДФЛ.
+const TAX_RATE: f64 = 13_f64;
+// Лимит расходов на покупку жилья для вычета.
+const PROPERTY_LIMIT: f64 = 2_000_000_f64;
+// Лимит уплаченных ипотечных процентов для вычета.
+const INTEREST_LIMIT: f64 = 3_000_000_f64;
+// Минимальный остаток вычета к переносу (1 копейка).
+const MIN_CARRY: f64 = 0.01;
+
+/// Имущественный вычет за год.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeductionYear {
+    pub year: i32,
+    // возврат с расходов на покупку жилья
+    pub property: f64,
+    // возврат с уплаченных процентов
+    pub interest: f64,
+    pub total: f64,
+}
+
+/// Имущественный вычет.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Deduction {
+    pub years: Vec<DeductionYear>,
+    pub total: f64,
+    // переплата за вычетом возврата налога
+    pub net_overpayment: f64,
+}
+
+/// Расчет имущественного вычета по графику платежей и годовому облагаемому доходу.
+/// Неиспользованный остаток вычета переносится на следующие годы.
+pub fn deduction_calc(
+    property_cost: f64,
+    payments: &[Payment],
+    taxable_income: f64,
+    overpayment: f64,
+) -> Deduction {
+    // годовой лимит возврата - уплаченный НДФЛ
+    let tax_paid: f64 = taxable_income.max(0_f64) * TAX_RATE / 100_f64;
+    // остатки к возврату
+    let mut property_left: f64 = property_cost.clamp(0_f64, PROPERTY_LIMIT) * TAX_RATE / 100_f64;
+    let mut interest_left: f64 = 0_f64;
+    let mut interest_base_left: f64 = INTEREST_LIMIT;
+
+    let mut years: Vec<DeductionYear> = Vec::new();
+    let (mut year, last_year): (i32, i32) = match (payments.first(), payments.last()) {
+        (Some(first), Some(last)) => (first.date.year(), last.date.year()),
+        _ => (0, -1),
+    };
+
+    while tax_paid > 0_f64
+        && (year <= last_year || property_left >= MIN_CARRY || interest_left >= MIN_CARRY)
+    {
+        // проценты, уплаченные за год, в пределах лимита
+        let interest_paid: f64 = payments
+            .iter()
+            .filter(|payment| payment.date.year() == year)
+            .map(|payment| payment.interest)
+            .sum::<f64>()
+            .min(interest_base_left);
+        interest_base_left -= interest_paid;
+        interest_left += interest_paid * TAX_RATE / 100_f64;
+        // сначала вычет с покупки, затем с процентов
+        let property: f64 = property_left.min(tax_paid);
+        let interest: f64 = interest_left.min(tax_paid - property);
+        property_left -= property;
+        interest_left -= interest;
+
+        years.push(DeductionYear {
+            year,
+            property,
+            interest,
+            total: property + interest,
+        });
+        year += 1;
+    }
+
+    let total: f64 = years.iter().map(|y| y.total).sum();
+    Deduction {
+        years,
+        total,
+        net_overpayment: overpayment - total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn payments() -> Vec<Payment> {
+        // два года по 600 тыс. процентов
+        (1..=24)
+            .map(|month| Payment {
+                month,
+                date: NaiveDate::from_ymd_opt(2024 + (month as i32 - 1) / 12, 1, 15).unwrap(),
+                interest: 50_000_f64,
+                ..Payment::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_deduction_calc() {
+        // доход 1 млн - возврат не больше 130 тыс. в год
+        let actual: Deduction =
+            deduction_calc(5_000_000_f64, &payments(), 1_000_000_f64, 1_200_000_f64);
+        assert_eq!(actual.years.len(), 4);
+        assert_eq!(actual.years[0].property, 130_000_f64);
+        assert_eq!(actual.years[0].interest, 0_f64);
+        assert_eq!(actual.years[1].property, 130_000_f64);
+        assert_eq!(actual.years[1].interest, 0_f64);
+        // остаток вычета с покупки и проценты за 2 года
+        assert_eq!(actual.years[2].property, 0_f64);
+        assert_eq!(actual.years[2].interest, 130_000_f64);
+        assert_eq!(actual.years[3].interest, 26_000_f64);
+        // 260 тыс. с покупки + 156 тыс. с процентов
+        assert_eq!(actual.total, 416_000_f64);
+        assert_eq!(actual.net_overpayment, 784_000_f64);
+    }
+
+    #[test]
+    fn test_deduction_calc_without_income() {
+        let actual: Deduction = deduction_calc(5_000_000_f64, &payments(), 0_f64, 1_200_000_f64);
+        assert!(actual.years.is_empty());
+        assert_eq!(actual.net_overpayment, 1_200_000_f64);
+    }
+}
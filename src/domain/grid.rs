@@ -0,0 +1,229 @@
+use crate::domain::mortgage::annuity_payment;
+use crate::errors::AppError;
+use crate::errors::AppError::InvalidGrid;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Максимальное количество ячеек сетки.
+const MAX_CELLS: usize = 10_000;
+// Точность округления ставки в сетке.
+const RATE_PRECISION: f64 = 1_000_000_f64;
+
+/// Диапазоны сетки чувствительности.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GridParams {
+    pub loan_sum: f64,
+    pub rate_from: f64,
+    pub rate_to: f64,
+    pub rate_step: f64,
+    pub years_from: u8,
+    pub years_to: u8,
+    pub years_step: u8,
+}
+
+/// Ячейка сетки.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GridCell {
+    pub years: u8,
+    pub monthly_payment: f64,
+    pub overpayment: f64,
+}
+
+/// Строка сетки для одной ставки.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GridRow {
+    pub rate: f64,
+    pub cells: Vec<GridCell>,
+}
+
+/// Сетка ежемесячных платежей и переплат по ставкам и срокам.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Grid {
+    pub loan_sum: f64,
+    pub rows: Vec<GridRow>,
+}
+
+impl GridParams {
+    /// Проверка диапазонов сетки.
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.loan_sum <= 0_f64
+            || self.rate_from <= 0_f64
+            || self.rate_step <= 0_f64
+            || self.rate_to < self.rate_from
+            || self.years_from == 0
+            || self.years_step == 0
+            || self.years_to < self.years_from
+        {
+            return Err(InvalidGrid);
+        }
+        // проверка размера сетки, переполнение при огромном диапазоне ставок - тоже ошибка
+        match self.rates_count().checked_mul(self.years().len()) {
+            Some(cells) if cells <= MAX_CELLS => {}
+            _ => return Err(InvalidGrid),
+        }
+
+        Ok(())
+    }
+
+    /// Количество ставок сетки.
+    fn rates_count(&self) -> usize {
+        (((self.rate_to - self.rate_from) / self.rate_step + 1e-9).floor() as usize)
+            .saturating_add(1)
+    }
+
+    /// Ставки сетки.
+    pub fn rates(&self) -> Vec<f64> {
+        (0..self.rates_count())
+            .map(|i| {
+                ((self.rate_from + self.rate_step * i as f64) * RATE_PRECISION).round()
+                    / RATE_PRECISION
+            })
+            .collect()
+    }
+
+    /// Сроки сетки в годах.
+    pub fn years(&self) -> Vec<u8> {
+        (self.years_from..=self.years_to)
+            .step_by(self.years_step as usize)
+            .collect()
+    }
+}
+
+/// Расчет сетки чувствительности.
+pub fn grid_calc(params: &GridParams) -> Result<Grid, AppError> {
+    params.check()?;
+
+    let years: Vec<u8> = params.years();
+    let rows: Vec<GridRow> = params
+        .rates()
+        .into_iter()
+        .map(|rate| GridRow {
+            rate,
+            cells: years
+                .iter()
+                .map(|&y| {
+                    let months: u16 = y as u16 * 12;
+                    let monthly_payment: f64 = annuity_payment(params.loan_sum, rate, months);
+                    GridCell {
+                        years: y,
+                        monthly_payment,
+                        overpayment: monthly_payment * months as f64 - params.loan_sum,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Grid {
+        loan_sum: params.loan_sum,
+        rows,
+    })
+}
+
+impl Grid {
+    /// CSV-представление сетки.
+    pub fn to_csv(&self) -> String {
+        let mut csv: String = String::from("rate,years,monthly_payment,overpayment\n");
+        for row in &self.rows {
+            for cell in &row.cells {
+                csv.push_str(&format!(
+                    "{},{},{},{}\n",
+                    row.rate, cell.years, cell.monthly_payment, cell.overpayment
+                ));
+            }
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> GridParams {
+        GridParams {
+            loan_sum: 4_000_000_f64,
+            rate_from: 6_f64,
+            rate_to: 14_f64,
+            rate_step: 0.5,
+            years_from: 5,
+            years_to: 30,
+            years_step: 5,
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        // Ok. Корректные диапазоны
+        assert!(params().check().is_ok());
+        // Err. Нулевой шаг ставки
+        let mut p: GridParams = params();
+        p.rate_step = 0_f64;
+        assert_eq!(p.check().err().unwrap(), InvalidGrid);
+        // Err. Перевернутый диапазон сроков
+        let mut p: GridParams = params();
+        p.years_to = 1;
+        assert_eq!(p.check().err().unwrap(), InvalidGrid);
+        // Err. Слишком большая сетка
+        let mut p: GridParams = params();
+        p.rate_step = 1e-12;
+        assert_eq!(p.check().err().unwrap(), InvalidGrid);
+        // Err. Количество ячеек переполняет usize
+        let p: GridParams = GridParams {
+            rate_from: 1_f64,
+            rate_step: 1_f64,
+            rate_to: 9223372036854775808_f64,
+            years_from: 10,
+            years_to: 20,
+            years_step: 10,
+            ..params()
+        };
+        assert_eq!(p.check().err().unwrap(), InvalidGrid);
+    }
+
+    #[test]
+    fn test_rates_and_years() {
+        let p: GridParams = params();
+        let rates: Vec<f64> = p.rates();
+        assert_eq!(rates.len(), 17);
+        assert_eq!(rates[1], 6.5);
+        assert_eq!(rates[16], 14_f64);
+        assert_eq!(p.years(), vec![5, 10, 15, 20, 25, 30]);
+    }
+
+    #[test]
+    fn test_grid_calc() {
+        let p: GridParams = GridParams {
+            rate_from: 8_f64,
+            rate_to: 8_f64,
+            years_from: 20,
+            years_to: 30,
+            years_step: 10,
+            ..params()
+        };
+        let actual: Grid = grid_calc(&p).unwrap();
+        let expected: Grid = Grid {
+            loan_sum: 4_000_000_f64,
+            rows: vec![GridRow {
+                rate: 8_f64,
+                cells: vec![
+                    GridCell {
+                        years: 20,
+                        monthly_payment: 33458_f64,
+                        overpayment: 4_029_920_f64,
+                    },
+                    GridCell {
+                        years: 30,
+                        monthly_payment: 29351_f64,
+                        overpayment: 6_566_360_f64,
+                    },
+                ],
+            }],
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(
+            actual.to_csv(),
+            "rate,years,monthly_payment,overpayment\n8,20,33458,4029920\n8,30,29351,6566360\n"
+        );
+    }
+}
@@ -1 +1,9 @@
+pub mod calendar;
+pub mod catalog;
+pub mod deduction;
+pub mod grid;
 pub mod mortgage;
+pub mod refinance;
+pub mod schedule;
+pub mod simulation;
+pub mod stress;
@@ -1,37 +1,151 @@
+use crate::domain::calendar::{Calendar, DateShift};
+use crate::domain::catalog::{
+    Catalog, ProgramRules, RateBreakdown, RateOption, Source, MAX_MONTHS,
+};
+use crate::domain::deduction::{deduction_calc, Deduction};
+use crate::domain::schedule::{payment_date, schedule, DayCount, Payment, ScheduleParams};
 use crate::errors::AppError;
-use crate::errors::AppError::{LoanProgramEmpty, LoanProgramMoreThanOne, MinInitialPayment};
+use crate::errors::AppError::{
+    Affordability, AgeAtMaturity, InvalidContribution, InvalidDisbursement, InvalidPaymentDay,
+    InvalidShares, InvalidTerm, LoanAboveLimit, LoanBelowLimit, LoanProgramEmpty,
+    LoanProgramMoreThanOne, MinInitialPayment,
+};
 use crate::schema::Request;
 use chrono::prelude::*;
 use chrono::Months;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::instrument;
+use utoipa::ToSchema;
 
 // Минимальная процент первоначального взноса.
 const MIN_INITIAL_PAYMENT_PERCENT: f64 = 20_f64;
-// Годовая процентаня ставка зарплатника.
-const SALARY: f64 = 8_f64;
-// Годовая процентаня ставка военного.
-const MILITARY: f64 = 9_f64;
-// Годовая базовая процентаня ставка.
-const BASE: f64 = 10_f64;
 
 // Структура ипотечной программы.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct Mortgage {
     pub params: Params,
     pub program: Program,
     pub aggregates: Aggregates,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deduction: Option<Deduction>,
+    // график платежей, пересчитанный после погашения субсидиями
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<Payment>,
+    // части кредита при комбинированном расчете
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tranches: Vec<Tranche>,
+    // версия каталога программ, по которому выполнен расчет
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub catalog_version: String,
+    // производственный календарь из каталога на момент расчета
+    #[serde(skip)]
+    pub calendar: Arc<Calendar>,
 }
 
 /// Параметры кредита.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Params {
     pub object_cost: f64,
+    // первоначальный взнос собственными средствами
     pub initial_payment: f64,
-    pub months: u8,
+    pub months: u16,
+    // годовой облагаемый доход для расчета имущественного вычета
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taxable_income: Option<f64>,
+    // прочие источники первоначального взноса и погашения
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributions: Vec<Contribution>,
+    // сведения о заемщике для проверки условий программы
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub borrower: Borrower,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_borrowers: Vec<Borrower>,
+    // дополнительные опции, изменяющие ставку
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<RateOption>,
+    // комбинированный расчет: часть сверх лимита по другой программе
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub combined: bool,
+    // выборка кредита траншами при строительстве
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disbursements: Vec<Disbursement>,
+    // дата выдачи, по умолчанию - текущая дата
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_date: Option<NaiveDate>,
+    // день месяца для платежей, по умолчанию - день выдачи
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_day: Option<u8>,
+    // конвенция начисления процентов
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub day_count: DayCount,
+    // перенос дат платежей с нерабочих дней
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub date_shift: DateShift,
+}
+
+/// Транш выборки кредита.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Disbursement {
+    // месяц выборки после выдачи, 0 - при выдаче
+    pub month: u16,
+    pub amount: f64,
+}
+
+/// Сведения о заемщике.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Borrower {
+    // участник накопительно-ипотечной системы военнослужащих
+    #[serde(default)]
+    pub military_service: bool,
+    // клиент зарплатного проекта
+    #[serde(default)]
+    pub salary_client: bool,
+    // количество детей
+    #[serde(default)]
+    pub children: u8,
+    // возраст, используется, если не указана дата рождения
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age: Option<u8>,
+    // код региона приобретения жилья
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    // ежемесячный доход
+    #[serde(default)]
+    pub income: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_of_birth: Option<NaiveDate>,
+    // доля в собственности, %
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share: Option<f64>,
+}
+
+impl Borrower {
+    /// Полных лет на дату выдачи: по дате рождения, без нее - указанный возраст.
+    pub fn age_at(&self, date: NaiveDate) -> Option<u8> {
+        match self.date_of_birth {
+            Some(date_of_birth) => Some(
+                date.years_since(date_of_birth)
+                    .unwrap_or_default()
+                    .min(u8::MAX as u32) as u8,
+            ),
+            None => self.age,
+        }
+    }
+}
+
+/// Взнос из отдельного источника.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Contribution {
+    pub source: Source,
+    pub amount: f64,
+    // месяц погашения после выдачи, без него - часть первоначального взноса
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub month: Option<u16>,
 }
 
 /// Ипотечная программа.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Program {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base: Option<bool>,
@@ -39,16 +153,119 @@ pub struct Program {
     pub military: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub salary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<bool>,
+}
+
+/// Вид ипотечной программы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgramKind {
+    Salary,
+    Military,
+    Base,
+    Family,
+}
+
+impl ProgramKind {
+    /// Все программы кредитования.
+    pub const ALL: [ProgramKind; 4] = [
+        ProgramKind::Salary,
+        ProgramKind::Military,
+        ProgramKind::Base,
+        ProgramKind::Family,
+    ];
+
+    /// Название программы, как в JSON.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgramKind::Salary => "salary",
+            ProgramKind::Military => "military",
+            ProgramKind::Base => "base",
+            ProgramKind::Family => "family",
+        }
+    }
+}
+
+impl Program {
+    /// Выбранная программа кредитования.
+    pub fn kind(&self) -> Option<ProgramKind> {
+        if self.salary.unwrap_or_default() {
+            Some(ProgramKind::Salary)
+        } else if self.military.unwrap_or_default() {
+            Some(ProgramKind::Military)
+        } else if self.base.unwrap_or_default() {
+            Some(ProgramKind::Base)
+        } else if self.family.unwrap_or_default() {
+            Some(ProgramKind::Family)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<ProgramKind> for Program {
+    fn from(kind: ProgramKind) -> Self {
+        match kind {
+            ProgramKind::Salary => Program {
+                salary: Some(true),
+                ..Program::default()
+            },
+            ProgramKind::Military => Program {
+                military: Some(true),
+                ..Program::default()
+            },
+            ProgramKind::Base => Program {
+                base: Some(true),
+                ..Program::default()
+            },
+            ProgramKind::Family => Program {
+                family: Some(true),
+                ..Program::default()
+            },
+        }
+    }
+}
+
+/// Часть кредита по отдельной программе.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Tranche {
+    pub program: ProgramKind,
+    pub loan_sum: f64,
+    pub rate: f64,
+    pub rate_breakdown: RateBreakdown,
+    pub monthly_payment: f64,
 }
 
 /// Расчитываемые агрегаты.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Aggregates {
     pub rate: f64,
     pub loan_sum: f64,
     pub monthly_payment: f64,
     pub overpayment: f64,
     pub last_payment_date: String,
+    // состав ставки: ставка программы и надбавки
+    #[serde(default)]
+    pub rate_breakdown: RateBreakdown,
+    // проценты, уплаченные в период строительства
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub construction_interest: Option<f64>,
+    // доля платежа в совокупном доходе заемщиков, %
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_to_income: Option<f64>,
+}
+
+impl Params {
+    /// Проверка срока кредита: от 1 месяца до максимального срока.
+    /// Общая для всех версий API и эндпоинтов расчета.
+    pub fn check(&self) -> Result<(), AppError> {
+        if !(1..=MAX_MONTHS).contains(&self.months) {
+            return Err(InvalidTerm);
+        }
+
+        Ok(())
+    }
 }
 
 impl Mortgage {
@@ -58,18 +275,175 @@ impl Mortgage {
             params: Params {
                 object_cost: req.object_cost,
                 initial_payment: req.initial_payment,
-                months: req.months,
+                months: req.months.into(),
+                taxable_income: req.taxable_income,
+                contributions: req.contributions,
+                borrower: req.borrower,
+                co_borrowers: req.co_borrowers,
+                options: req.options,
+                combined: req.combined,
+                disbursements: req.disbursements,
+                issue_date: req.issue_date,
+                payment_day: req.payment_day,
+                day_count: req.day_count,
+                date_shift: req.date_shift,
             },
             program: Program {
                 base: req.program.base,
                 military: req.program.military,
                 salary: req.program.salary,
+                family: req.program.family,
             },
             aggregates: Aggregates::default(),
+            deduction: None,
+            schedule: Vec::new(),
+            tranches: Vec::new(),
+            catalog_version: String::new(),
+            calendar: Arc::default(),
+        }
+    }
+
+    /// Полный расчет кредита по правилам программ из каталога.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(program = self.program.kind().map(|kind| kind.as_str()), months = self.params.months),
+        err(level = "debug")
+    )]
+    pub fn execute(&mut self, catalog: &Catalog) -> Result<(), AppError> {
+        // проверка срока
+        self.params.check()?;
+        // проверка на наличие больше 1 программы в запросе
+        self.loan_program_check()?;
+        // версия каталога и производственный календарь
+        self.catalog_version = catalog.version.clone();
+        self.calendar = Arc::clone(&catalog.calendar);
+        // правила выбранной программы
+        let rules: &ProgramRules = catalog.rules(self.program.kind().ok_or(LoanProgramEmpty)?)?;
+        // проверка условий программы: только для основного заемщика, созаемщики не проверяются
+        rules
+            .eligibility
+            .check(&self.params.borrower, self.issue_date())?;
+        // проверка долей заемщиков
+        self.shares_check()?;
+        // проверка возраста заемщиков на дату погашения
+        self.age_at_maturity_check(catalog.max_age_at_maturity)?;
+        // проверка взносов
+        self.contributions_check()?;
+        // проверка минимальной суммы первоначального взноса
+        self.min_initial_payment_check(rules)?;
+        // расчет суммы кредита
+        self.loan_sum_calc();
+        // проверка графика выборки траншей
+        self.disbursements_check()?;
+        // проверка дня платежа
+        self.payment_day_check()?;
+        // проверка лимитов суммы кредита
+        let limit: Option<f64> = self.loan_limits_check(rules)?;
+        // Определение процентной ставки
+        self.rate_calc(rules)?;
+        // расчет ежемесячного платежа
+        self.monthly_payment_calc();
+        // комбинированный расчет части сверх лимита
+        if let Some(max_loan) = limit {
+            self.tranches_calc(catalog, rules, max_loan)?;
+        }
+        // проверка платежа по совокупному доходу
+        self.affordability_check(catalog.max_payment_to_income)?;
+        // расчет процентов в период строительства
+        self.construction_interest_calc();
+        // расчет переплаты
+        self.overpayment_calc();
+        // расчет даты последнего платежа
+        self.last_payment_date_calc();
+        // график платежей со строительством и погашением субсидиями
+        self.schedule_calc();
+        // расчет имущественного вычета
+        self.deduction_calc();
+
+        Ok(())
+    }
+
+    /// Полная стоимость кредита (сумма всех платежей).
+    pub fn total_cost(&self) -> f64 {
+        self.aggregates.loan_sum + self.aggregates.overpayment
+    }
+
+    /// Период строительства: месяц выборки последнего транша.
+    pub fn construction_months(&self) -> u16 {
+        self.params
+            .disbursements
+            .iter()
+            .map(|d| d.month)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Срок аннуитетных платежей после полной выборки кредита.
+    pub fn annuity_months(&self) -> u16 {
+        self.params
+            .months
+            .saturating_sub(self.construction_months())
+    }
+
+    /// Проверка графика выборки: транши выбираются до конца срока
+    /// и в сумме равны сумме кредита.
+    #[instrument(level = "debug", skip_all)]
+    pub fn disbursements_check(&self) -> Result<(), AppError> {
+        if self.params.disbursements.is_empty() {
+            return Ok(());
+        }
+        let total: f64 = self.params.disbursements.iter().map(|d| d.amount).sum();
+        if self
+            .params
+            .disbursements
+            .iter()
+            .any(|d| d.amount <= 0_f64 || d.month >= self.params.months)
+            || (total - self.aggregates.loan_sum).abs() > 1e-6
+        {
+            return Err(InvalidDisbursement);
+        }
+
+        Ok(())
+    }
+
+    /// Проверка взносов: сумма не отрицательная, погашение после выдачи - с 1 месяца до конца срока.
+    #[instrument(level = "debug", skip_all)]
+    pub fn contributions_check(&self) -> Result<(), AppError> {
+        if self.params.contributions.iter().any(|c| {
+            c.amount < 0_f64
+                || c.month
+                    .is_some_and(|month| month == 0 || month > self.params.months)
+        }) {
+            return Err(InvalidContribution);
+        }
+
+        Ok(())
+    }
+
+    /// Проверка дня платежа: день месяца от 1 до 31.
+    #[instrument(level = "debug", skip_all)]
+    pub fn payment_day_check(&self) -> Result<(), AppError> {
+        if self
+            .params
+            .payment_day
+            .is_some_and(|day| !(1..=31).contains(&day))
+        {
+            return Err(InvalidPaymentDay);
         }
+
+        Ok(())
+    }
+
+    /// Дата выдачи кредита.
+    pub fn issue_date(&self) -> NaiveDate {
+        self.params
+            .issue_date
+            .unwrap_or_else(|| Utc::now().date_naive())
     }
 
     /// Проверка на наличие больше 1 программы в запросе.
+    #[instrument(level = "debug", skip_all)]
     pub fn loan_program_check(&mut self) -> Result<(), AppError> {
         let mut counter: i8 = i8::default();
 
@@ -77,6 +451,7 @@ impl Mortgage {
             self.program.salary.unwrap_or_default(),
             self.program.military.unwrap_or_default(),
             self.program.base.unwrap_or_default(),
+            self.program.family.unwrap_or_default(),
         ];
 
         for program in check_vec {
@@ -97,63 +472,426 @@ impl Mortgage {
     }
 
     /// Проверка минимальной суммы первоначального взноса.
-    pub fn min_initial_payment_check(&self) -> Result<(), AppError> {
-        if self.params.initial_payment
-            < self.params.object_cost * MIN_INITIAL_PAYMENT_PERCENT / 100_f64
-        {
+    /// Учитываются только источники, засчитываемые программой.
+    #[instrument(level = "debug", skip_all)]
+    pub fn min_initial_payment_check(&self, rules: &ProgramRules) -> Result<(), AppError> {
+        let counted: f64 = self
+            .initial_contributions()
+            .filter(|c| rules.counted_sources.contains(&c.source))
+            .map(|c| c.amount)
+            .sum::<f64>()
+            + if rules.counted_sources.contains(&Source::OwnFunds) {
+                self.params.initial_payment
+            } else {
+                0_f64
+            };
+        if counted < self.params.object_cost * MIN_INITIAL_PAYMENT_PERCENT / 100_f64 {
             return Err(MinInitialPayment);
         }
 
         Ok(())
     }
 
+    /// Взносы, входящие в первоначальный взнос.
+    fn initial_contributions(&self) -> impl Iterator<Item = &Contribution> {
+        self.params
+            .contributions
+            .iter()
+            .filter(|c| c.month.is_none())
+    }
+
+    /// Заемщик и созаемщики.
+    fn borrowers(&self) -> impl Iterator<Item = &Borrower> {
+        std::iter::once(&self.params.borrower).chain(self.params.co_borrowers.iter())
+    }
+
+    /// Проверка долей заемщиков: если доли указаны, в сумме они составляют 100%.
+    #[instrument(level = "debug", skip_all)]
+    pub fn shares_check(&self) -> Result<(), AppError> {
+        if self.borrowers().all(|b| b.share.is_none()) {
+            return Ok(());
+        }
+        let total: f64 = self.borrowers().filter_map(|b| b.share).sum();
+        if self
+            .borrowers()
+            .any(|b| b.share.is_some_and(|share| share <= 0_f64))
+            || (total - 100_f64).abs() > 1e-6
+        {
+            return Err(InvalidShares);
+        }
+
+        Ok(())
+    }
+
+    /// Проверка, что кредит погашается до достижения каждым заемщиком и созаемщиком
+    /// максимального возраста: по дате рождения, без нее - возраст на дату погашения `age + months / 12`.
+    /// Заемщики без возраста не проверяются. В ошибке возвращается максимально допустимый срок.
+    #[instrument(level = "debug", skip_all)]
+    pub fn age_at_maturity_check(&self, max_age: u8) -> Result<(), AppError> {
+        let max_months: Option<u32> = self
+            .borrowers()
+            .filter_map(|b| match b.date_of_birth {
+                Some(date_of_birth) => Some(max_term(date_of_birth, max_age, self.issue_date())),
+                None => b.age.map(|age| max_age.saturating_sub(age) as u32 * 12),
+            })
+            .min();
+        if let Some(max_months) = max_months {
+            if self.params.months as u32 > max_months {
+                return Err(AgeAtMaturity {
+                    max_age,
+                    max_months,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Совокупный ежемесячный доход заемщиков.
+    pub fn income(&self) -> f64 {
+        self.borrowers().map(|b| b.income).sum()
+    }
+
+    /// Проверка доли платежа в совокупном доходе заемщиков.
+    /// Без указанного дохода проверка не выполняется.
+    #[instrument(level = "debug", skip_all)]
+    pub fn affordability_check(&mut self, max_payment_to_income: f64) -> Result<(), AppError> {
+        let income: f64 = self.income();
+        if income <= 0_f64 {
+            return Ok(());
+        }
+        let payment_to_income: f64 = self.aggregates.monthly_payment / income * 100_f64;
+        self.aggregates.payment_to_income = Some(payment_to_income);
+        if payment_to_income > max_payment_to_income {
+            return Err(Affordability);
+        }
+
+        Ok(())
+    }
+
     /// Расчет суммы кредита.
+    #[instrument(level = "debug", skip_all)]
     pub fn loan_sum_calc(&mut self) {
-        self.aggregates.loan_sum = self.params.object_cost - self.params.initial_payment
+        self.aggregates.loan_sum = self.params.object_cost
+            - self.params.initial_payment
+            - self.initial_contributions().map(|c| c.amount).sum::<f64>()
+    }
+
+    /// Проверка лимитов суммы кредита с учетом региона.
+    /// Возвращает лимит, если сумма сверх него рассчитывается комбинированно.
+    #[instrument(level = "debug", skip_all)]
+    pub fn loan_limits_check(&self, rules: &ProgramRules) -> Result<Option<f64>, AppError> {
+        if let Some(min_loan) = rules.min_loan {
+            if self.aggregates.loan_sum < min_loan {
+                return Err(LoanBelowLimit { min_loan });
+            }
+        }
+        match rules.max_loan(self.params.borrower.region.as_ref()) {
+            Some(max_loan) if self.aggregates.loan_sum > max_loan => {
+                if self.params.combined && rules.overflow_program.is_some() {
+                    Ok(Some(max_loan))
+                } else {
+                    Err(LoanAboveLimit { max_loan })
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Комбинированный расчет: часть в пределах лимита по ставке программы,
+    /// остаток по ставке программы для суммы сверх лимита.
+    /// Ставка кредита - средневзвешенная по суммам частей.
+    #[instrument(level = "debug", skip_all)]
+    pub fn tranches_calc(
+        &mut self,
+        catalog: &Catalog,
+        rules: &ProgramRules,
+        max_loan: f64,
+    ) -> Result<(), AppError> {
+        let overflow_program: ProgramKind =
+            rules.overflow_program.ok_or(LoanAboveLimit { max_loan })?;
+        let overflow_rules: &ProgramRules = catalog.rules(overflow_program)?;
+        let overflow_breakdown: RateBreakdown = RateBreakdown {
+            base_rate: overflow_rules.rate,
+            adjustments: overflow_rules.rate_adjustments(
+                self.ltv(),
+                self.params.months,
+                &self.params.options,
+            )?,
+        };
+        let overflow_rate: f64 = overflow_breakdown.base_rate
+            + overflow_breakdown
+                .adjustments
+                .iter()
+                .map(|a| a.value)
+                .sum::<f64>();
+        let overflow_sum: f64 = self.aggregates.loan_sum - max_loan;
+
+        self.tranches = vec![
+            Tranche {
+                program: self.program.kind().ok_or(LoanProgramEmpty)?,
+                loan_sum: max_loan,
+                rate: self.aggregates.rate,
+                rate_breakdown: self.aggregates.rate_breakdown.clone(),
+                monthly_payment: annuity_payment(
+                    max_loan,
+                    self.aggregates.rate,
+                    self.annuity_months(),
+                ),
+            },
+            Tranche {
+                program: overflow_program,
+                loan_sum: overflow_sum,
+                rate: overflow_rate,
+                rate_breakdown: overflow_breakdown,
+                monthly_payment: annuity_payment(
+                    overflow_sum,
+                    overflow_rate,
+                    self.annuity_months(),
+                ),
+            },
+        ];
+        self.aggregates.rate = self
+            .tranches
+            .iter()
+            .map(|t| t.rate * t.loan_sum)
+            .sum::<f64>()
+            / self.aggregates.loan_sum;
+        self.aggregates.monthly_payment = self.tranches.iter().map(|t| t.monthly_payment).sum();
+
+        Ok(())
     }
 
-    /// Определение процентной ставки.
-    pub fn rate_calc(&mut self) {
-        if self.program.salary.unwrap_or_default() {
-            self.aggregates.rate = SALARY;
-        } else if self.program.military.unwrap_or_default() {
-            self.aggregates.rate = MILITARY;
-        } else if self.program.base.unwrap_or_default() {
-            self.aggregates.rate = BASE;
+    /// Определение процентной ставки: ставка программы с надбавками по LTV, сроку и опциям.
+    #[instrument(level = "debug", skip_all)]
+    pub fn rate_calc(&mut self, rules: &ProgramRules) -> Result<(), AppError> {
+        self.aggregates.rate_breakdown = RateBreakdown {
+            base_rate: rules.rate,
+            adjustments: rules.rate_adjustments(
+                self.ltv(),
+                self.params.months,
+                &self.params.options,
+            )?,
+        };
+        self.aggregates.rate = self.aggregates.rate_breakdown.base_rate
+            + self
+                .aggregates
+                .rate_breakdown
+                .adjustments
+                .iter()
+                .map(|a| a.value)
+                .sum::<f64>();
+
+        Ok(())
+    }
+
+    /// Отношение суммы кредита к стоимости объекта (LTV), %.
+    pub fn ltv(&self) -> f64 {
+        if self.params.object_cost <= 0_f64 {
+            return 0_f64;
         }
+        self.aggregates.loan_sum / self.params.object_cost * 100_f64
     }
 
     /// Pасчет ежемесячного аннуитетного платежа
+    #[instrument(level = "debug", skip_all)]
     pub fn monthly_payment_calc(&mut self) {
-        let monthly_rate: f64 = self.aggregates.rate / 100_f64 / 12_f64;
-        let pow_monthly_rate: f64 = (1_f64 + monthly_rate).powf(self.params.months as f64);
+        self.aggregates.monthly_payment = annuity_payment(
+            self.aggregates.loan_sum,
+            self.aggregates.rate,
+            self.annuity_months(),
+        )
+    }
 
-        self.aggregates.monthly_payment =
-            (self.aggregates.loan_sum * monthly_rate * pow_monthly_rate
-                / (pow_monthly_rate - 1_f64))
-                // округление вверх
-                .ceil()
+    /// Расчет процентов в период строительства по графику платежей.
+    #[instrument(level = "debug", skip_all)]
+    pub fn construction_interest_calc(&mut self) {
+        if self.params.disbursements.is_empty() {
+            return;
+        }
+        self.aggregates.construction_interest = Some(
+            self.schedule()
+                .iter()
+                .take(self.construction_months() as usize)
+                .map(|p| p.interest)
+                .sum(),
+        );
     }
 
-    /// Расчет переплаты за весь срок кредита.
+    /// Расчет переплаты за весь срок кредита, включая период строительства.
+    /// При начислении по фактическим дням или погашении субсидиями - сумма процентов по графику платежей.
+    #[instrument(level = "debug", skip_all)]
     pub fn overpayment_calc(&mut self) {
-        self.aggregates.overpayment =
-            self.aggregates.monthly_payment * self.params.months as f64 - self.aggregates.loan_sum
+        if self.params.day_count != DayCount::Monthly || self.has_subsidies() {
+            self.aggregates.overpayment = self.schedule().iter().map(|p| p.interest).sum();
+            return;
+        }
+        self.aggregates.overpayment = self.aggregates.monthly_payment * self.annuity_months() as f64
+            + self.aggregates.construction_interest.unwrap_or_default()
+            - self.aggregates.loan_sum
     }
 
     /// Расчет даты последнего платежа.
+    /// При погашении субсидиями кредит может закрыться досрочно - дата по графику платежей.
+    #[instrument(level = "debug", skip_all)]
     pub fn last_payment_date_calc(&mut self) {
-        self.aggregates.last_payment_date = Utc::now()
-            .checked_add_months(Months::new(self.params.months as u32))
-            .unwrap()
+        if self.has_subsidies() {
+            if let Some(last) = self.schedule().last() {
+                self.aggregates.last_payment_date = last.date.format("%Y-%m-%d").to_string();
+                return;
+            }
+        }
+        self.aggregates.last_payment_date = self
+            .calendar
+            .adjust(
+                payment_date(
+                    self.issue_date(),
+                    self.params.payment_day,
+                    self.params.months,
+                ),
+                self.params.date_shift,
+            )
             .format("%Y-%m-%d")
             .to_string();
     }
+
+    /// График платежей с даты выдачи с учетом выборки траншами и погашения субсидиями.
+    pub fn schedule(&self) -> Vec<Payment> {
+        schedule(&self.schedule_params())
+    }
+
+    /// Параметры графика платежей кредита.
+    pub fn schedule_params(&self) -> ScheduleParams {
+        ScheduleParams {
+            loan_sum: self.aggregates.loan_sum,
+            rate: self.aggregates.rate,
+            months: self.params.months,
+            monthly_payment: self.aggregates.monthly_payment,
+            issue_date: self.issue_date(),
+            subsidies: self
+                .params
+                .contributions
+                .iter()
+                .filter_map(|c| c.month.map(|month| (month, c.amount)))
+                .collect(),
+            disbursements: self
+                .params
+                .disbursements
+                .iter()
+                .map(|d| (d.month, d.amount))
+                .collect(),
+            rate_changes: Vec::new(),
+            day_count: self.params.day_count,
+            payment_day: self.params.payment_day,
+            date_shift: self.params.date_shift,
+            calendar: Arc::clone(&self.calendar),
+        }
+    }
+
+    /// Есть ли погашения субсидиями после выдачи.
+    fn has_subsidies(&self) -> bool {
+        self.params.contributions.iter().any(|c| c.month.is_some())
+    }
+
+    /// Расчет графика платежей, если есть выборка траншами, погашения субсидиями
+    /// после выдачи или начисление процентов по фактическим дням.
+    #[instrument(level = "debug", skip_all)]
+    pub fn schedule_calc(&mut self) {
+        if !self.params.disbursements.is_empty()
+            || self.params.day_count != DayCount::Monthly
+            || self.has_subsidies()
+        {
+            self.schedule = self.schedule();
+        }
+    }
+
+    /// Расчет имущественного вычета, если указан облагаемый доход.
+    #[instrument(level = "debug", skip_all)]
+    pub fn deduction_calc(&mut self) {
+        if let Some(taxable_income) = self.params.taxable_income {
+            self.deduction = Some(deduction_calc(
+                self.params.object_cost,
+                &self.schedule(),
+                taxable_income,
+                self.aggregates.overpayment,
+            ));
+        }
+    }
+}
+
+/// Максимальный срок в месяцах, при котором кредит, выданный в дату выдачи,
+/// погашается до достижения заемщиком максимального возраста.
+pub fn max_term(date_of_birth: NaiveDate, max_age: u8, issue_date: NaiveDate) -> u32 {
+    let limit: NaiveDate = date_of_birth
+        .checked_add_months(Months::new(max_age as u32 * 12))
+        .unwrap_or(NaiveDate::MAX);
+    let mut months: i32 =
+        (limit.year() - issue_date.year()) * 12 + limit.month() as i32 - issue_date.month() as i32;
+    // погашение строго раньше даты достижения возраста
+    while months > 0
+        && issue_date
+            .checked_add_months(Months::new(months as u32))
+            .is_none_or(|maturity| maturity >= limit)
+    {
+        months -= 1;
+    }
+
+    months.max(0) as u32
+}
+
+/// Значение по умолчанию не сериализуется: ответ v1 без новых параметров не меняется.
+pub fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Аннуитетный ежемесячный платеж по сумме, годовой ставке и сроку.
+/// При нулевой ставке сумма делится на срок равными долями, без срока - вся сумма.
+pub fn annuity_payment(loan_sum: f64, rate: f64, months: u16) -> f64 {
+    if months == 0 {
+        return loan_sum.ceil();
+    }
+    if rate == 0_f64 {
+        return (loan_sum / months as f64).ceil();
+    }
+    let monthly_rate: f64 = rate / 100_f64 / 12_f64;
+    let pow_monthly_rate: f64 = (1_f64 + monthly_rate).powf(months as f64);
+
+    (loan_sum * monthly_rate * pow_monthly_rate / (pow_monthly_rate - 1_f64))
+        // округление вверх
+        .ceil()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::catalog::{RateAdjustment, RateFactor, BASE, FAMILY, MILITARY, SALARY};
+    use crate::errors::AppError::{MilitaryServiceRequired, ProgramUnavailable};
+
+    #[test]
+    fn test_params_check() {
+        let mut params: Params = Params {
+            months: MAX_MONTHS,
+            ..Params::default()
+        };
+        // Ok. Максимальный срок
+        assert!(params.check().is_ok());
+        // Err. Срок больше максимального
+        params.months = MAX_MONTHS + 1;
+        assert_eq!(params.check().err().unwrap(), InvalidTerm);
+        // Err. Нулевой срок
+        params.months = 0;
+        assert_eq!(params.check().err().unwrap(), InvalidTerm);
+        // Err. Расчет с нулевым сроком
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            params,
+            ..Mortgage::default()
+        };
+        assert_eq!(
+            loan.execute(&Catalog::default()).err().unwrap(),
+            InvalidTerm
+        );
+    }
 
     #[test]
     fn test_loan_program_check() {
@@ -161,7 +899,7 @@ mod tests {
         let mut loan: Mortgage = Mortgage::default();
         loan.program.salary = Some(true);
         let actual: bool = loan.loan_program_check().is_ok();
-        assert_eq!(actual, true);
+        assert!(actual);
         // Err. Выбрано 2 прораммы кредитования
         let mut loan: Mortgage = Mortgage::default();
         loan.program.salary = Some(true);
@@ -174,17 +912,38 @@ mod tests {
         assert_eq!(actual, LoanProgramEmpty)
     }
 
+    fn contribution(source: Source, amount: f64, month: Option<u16>) -> Contribution {
+        Contribution {
+            source,
+            amount,
+            month,
+        }
+    }
+
     #[test]
     fn test_min_initial_payment_check() {
+        let catalog: Catalog = Catalog::default();
+        let rules: &ProgramRules = catalog.rules(ProgramKind::Salary).unwrap();
         let mut loan: Mortgage = Mortgage::default();
         loan.params.object_cost = 100_f64;
         // Ok. Допустимый минимальный платеж
         loan.params.initial_payment = 25_f64;
-        let actual: bool = loan.min_initial_payment_check().is_ok();
-        assert_eq!(actual, true);
+        let actual: bool = loan.min_initial_payment_check(rules).is_ok();
+        assert!(actual);
         // Err. Первоначальный взнос меньше минимально допустимого.
         loan.params.initial_payment = 10_f64;
-        let actual: AppError = loan.min_initial_payment_check().err().unwrap();
+        let actual: AppError = loan.min_initial_payment_check(rules).err().unwrap();
+        assert_eq!(actual, MinInitialPayment);
+        // Ok. Материнский капитал засчитывается программой
+        loan.params.contributions = vec![contribution(Source::MaternityCapital, 10_f64, None)];
+        assert!(loan.min_initial_payment_check(rules).is_ok());
+        // Err. Господдержка не засчитывается зарплатной программой
+        loan.params.contributions = vec![contribution(Source::StateSubsidy, 10_f64, None)];
+        let actual: AppError = loan.min_initial_payment_check(rules).err().unwrap();
+        assert_eq!(actual, MinInitialPayment);
+        // Err. Погашение после выдачи не входит в первоначальный взнос
+        loan.params.contributions = vec![contribution(Source::MaternityCapital, 10_f64, Some(1))];
+        let actual: AppError = loan.min_initial_payment_check(rules).err().unwrap();
         assert_eq!(actual, MinInitialPayment)
     }
 
@@ -194,25 +953,201 @@ mod tests {
         loan.params.object_cost = 100_f64;
         loan.params.initial_payment = 25_f64;
         loan.loan_sum_calc();
-        assert_eq!(loan.aggregates.loan_sum, 75_f64)
+        assert_eq!(loan.aggregates.loan_sum, 75_f64);
+        // Ok. Взносы из прочих источников уменьшают сумму кредита
+        loan.params.contributions = vec![
+            contribution(Source::Employer, 5_f64, None),
+            contribution(Source::StateSubsidy, 10_f64, Some(3)),
+        ];
+        loan.loan_sum_calc();
+        assert_eq!(loan.aggregates.loan_sum, 70_f64)
     }
 
     #[test]
     fn test_rate_calc() {
+        let catalog: Catalog = Catalog::default();
         let mut loan: Mortgage = Mortgage::default();
-        loan.program.salary = Some(true);
-        loan.rate_calc();
+        loan.params.object_cost = 100_f64;
+        loan.aggregates.loan_sum = 70_f64;
+
+        loan.rate_calc(catalog.rules(ProgramKind::Salary).unwrap())
+            .unwrap();
         assert_eq!(loan.aggregates.rate, SALARY);
 
-        let mut loan: Mortgage = Mortgage::default();
-        loan.program.military = Some(true);
-        loan.rate_calc();
+        loan.rate_calc(catalog.rules(ProgramKind::Military).unwrap())
+            .unwrap();
         assert_eq!(loan.aggregates.rate, MILITARY);
 
+        loan.rate_calc(catalog.rules(ProgramKind::Base).unwrap())
+            .unwrap();
+        assert_eq!(loan.aggregates.rate, BASE);
+        assert_eq!(loan.aggregates.rate_breakdown.base_rate, BASE);
+        assert!(loan.aggregates.rate_breakdown.adjustments.is_empty());
+
+        loan.rate_calc(catalog.rules(ProgramKind::Family).unwrap())
+            .unwrap();
+        assert_eq!(loan.aggregates.rate, FAMILY);
+        // Ok. Скидка при LTV ниже 50%
+        loan.aggregates.loan_sum = 40_f64;
+        loan.rate_calc(catalog.rules(ProgramKind::Base).unwrap())
+            .unwrap();
+        assert!((loan.aggregates.rate - 9.7).abs() < 1e-9);
+        assert_eq!(
+            loan.aggregates.rate_breakdown.adjustments,
+            vec![RateAdjustment {
+                factor: RateFactor::Ltv,
+                value: -0.3
+            }]
+        );
+        // Ok. Надбавка при LTV выше 80%
+        loan.aggregates.loan_sum = 90_f64;
+        loan.rate_calc(catalog.rules(ProgramKind::Base).unwrap())
+            .unwrap();
+        assert_eq!(loan.aggregates.rate, 10.5);
+        // Ok. Отказ от страхования жизни
+        loan.params.options = vec![RateOption::NoLifeInsurance];
+        loan.rate_calc(catalog.rules(ProgramKind::Base).unwrap())
+            .unwrap();
+        assert_eq!(loan.aggregates.rate, 11.5);
+        assert_eq!(loan.aggregates.rate_breakdown.adjustments.len(), 2)
+    }
+
+    #[test]
+    fn test_loan_limits_check() {
+        let catalog: Catalog = Catalog::default();
+        let mut rules: ProgramRules = catalog.rules(ProgramKind::Family).unwrap().clone();
+        let mut loan: Mortgage = Mortgage::default();
+        // Ok. Сумма в пределах лимита
+        loan.aggregates.loan_sum = 6_000_000_f64;
+        assert_eq!(loan.loan_limits_check(&rules).unwrap(), None);
+        // Err. Сумма выше лимита без комбинированного расчета
+        loan.aggregates.loan_sum = 8_000_000_f64;
+        assert_eq!(
+            loan.loan_limits_check(&rules).err().unwrap(),
+            LoanAboveLimit {
+                max_loan: 6_000_000_f64
+            }
+        );
+        // Ok. Комбинированный расчет
+        loan.params.combined = true;
+        assert_eq!(loan.loan_limits_check(&rules).unwrap(), Some(6_000_000_f64));
+        // Ok. Региональный лимит
+        loan.params.borrower.region = Some("77".to_string());
+        assert_eq!(loan.loan_limits_check(&rules).unwrap(), None);
+        // Err. Сумма ниже минимальной
+        rules.min_loan = Some(10_000_000_f64);
+        assert_eq!(
+            loan.loan_limits_check(&rules).err().unwrap(),
+            LoanBelowLimit {
+                min_loan: 10_000_000_f64
+            }
+        );
+    }
+
+    #[test]
+    fn test_tranches_calc() {
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Family),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 10_000_000_f64;
+        loan.params.initial_payment = 2_000_000_f64;
+        loan.params.months = 240;
+        loan.params.borrower.children = 2;
+        // Err. Сумма выше лимита
+        assert_eq!(
+            loan.execute(&Catalog::default()).err().unwrap(),
+            LoanAboveLimit {
+                max_loan: 6_000_000_f64
+            }
+        );
+        // Ok. 6 млн по семейной ставке, 2 млн по базовой
+        loan.params.combined = true;
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        assert_eq!(loan.tranches.len(), 2);
+        assert_eq!(loan.tranches[0].program, ProgramKind::Family);
+        assert_eq!(loan.tranches[0].loan_sum, 6_000_000_f64);
+        assert_eq!(loan.tranches[0].rate, FAMILY);
+        assert_eq!(loan.tranches[1].program, ProgramKind::Base);
+        assert_eq!(loan.tranches[1].loan_sum, 2_000_000_f64);
+        assert_eq!(loan.tranches[1].rate, BASE);
+        assert_eq!(loan.aggregates.rate, 7_f64);
+        assert_eq!(
+            loan.aggregates.monthly_payment,
+            loan.tranches[0].monthly_payment + loan.tranches[1].monthly_payment
+        );
+    }
+
+    #[test]
+    fn test_disbursements_check() {
         let mut loan: Mortgage = Mortgage::default();
-        loan.program.base = Some(true);
-        loan.rate_calc();
-        assert_eq!(loan.aggregates.rate, BASE)
+        loan.params.months = 12;
+        loan.aggregates.loan_sum = 1200_f64;
+        // Ok. Без траншей
+        assert!(loan.disbursements_check().is_ok());
+        // Ok. Транши в сумме равны кредиту
+        loan.params.disbursements = vec![
+            Disbursement {
+                month: 0,
+                amount: 600_f64,
+            },
+            Disbursement {
+                month: 3,
+                amount: 600_f64,
+            },
+        ];
+        assert!(loan.disbursements_check().is_ok());
+        // Err. Транши в сумме меньше кредита
+        loan.params.disbursements[1].amount = 500_f64;
+        assert_eq!(
+            loan.disbursements_check().err().unwrap(),
+            InvalidDisbursement
+        );
+        // Err. Транш в конце срока
+        loan.params.disbursements[1].amount = 600_f64;
+        loan.params.disbursements[1].month = 12;
+        assert_eq!(
+            loan.disbursements_check().err().unwrap(),
+            InvalidDisbursement
+        );
+    }
+
+    #[test]
+    fn test_construction() {
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 1500_f64;
+        loan.params.initial_payment = 300_f64;
+        loan.params.months = 24;
+        loan.params.disbursements = vec![
+            Disbursement {
+                month: 0,
+                amount: 600_f64,
+            },
+            Disbursement {
+                month: 12,
+                amount: 600_f64,
+            },
+        ];
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        // проценты на 600 в течение 12 месяцев
+        assert!((loan.aggregates.construction_interest.unwrap() - 60_f64).abs() < 1e-9);
+        // аннуитет на 1200 на оставшиеся 12 месяцев
+        assert_eq!(
+            loan.aggregates.monthly_payment,
+            annuity_payment(1200_f64, BASE, 12)
+        );
+        assert!(
+            (loan.aggregates.overpayment
+                - (loan.aggregates.monthly_payment * 12_f64 + 60_f64 - 1200_f64))
+                .abs()
+                < 1e-9
+        );
+        assert_eq!(loan.schedule.len(), 24);
+        assert_eq!(loan.schedule[11].principal, 0_f64);
+        assert_eq!(loan.schedule[12].payment, loan.aggregates.monthly_payment);
     }
 
     #[test]
@@ -234,6 +1169,115 @@ mod tests {
         assert_eq!(loan.aggregates.monthly_payment, 9651_f64);
     }
 
+    #[test]
+    fn test_shares_check() {
+        let mut loan: Mortgage = Mortgage::default();
+        // Ok. Доли не указаны
+        loan.params.co_borrowers = vec![Borrower::default()];
+        assert!(loan.shares_check().is_ok());
+        // Ok. Доли в сумме 100%
+        loan.params.borrower.share = Some(60_f64);
+        loan.params.co_borrowers[0].share = Some(40_f64);
+        assert!(loan.shares_check().is_ok());
+        // Err. Доли в сумме не 100%
+        loan.params.co_borrowers[0].share = Some(30_f64);
+        assert_eq!(loan.shares_check().err().unwrap(), InvalidShares);
+    }
+
+    #[test]
+    fn test_max_term() {
+        let issue_date: NaiveDate = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        // 75 лет исполняется 2044-05-21 - последний допустимый платеж 2044-05-20
+        let date_of_birth: NaiveDate = NaiveDate::from_ymd_opt(1969, 5, 21).unwrap();
+        assert_eq!(max_term(date_of_birth, 75, issue_date), 240);
+        // погашение в день рождения не допускается
+        let date_of_birth: NaiveDate = NaiveDate::from_ymd_opt(1969, 5, 20).unwrap();
+        assert_eq!(max_term(date_of_birth, 75, issue_date), 239);
+        // возраст уже превышен
+        let date_of_birth: NaiveDate = NaiveDate::from_ymd_opt(1940, 1, 1).unwrap();
+        assert_eq!(max_term(date_of_birth, 75, issue_date), 0);
+    }
+
+    #[test]
+    fn test_age_at_maturity_check() {
+        let today: NaiveDate = Utc::now().date_naive();
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.months = 240;
+        // Ok. Ни дата рождения, ни возраст не указаны
+        assert!(loan.age_at_maturity_check(75).is_ok());
+        // Ok. Заемщику 30 лет
+        loan.params.borrower.date_of_birth = today.checked_sub_months(Months::new(30 * 12));
+        assert!(loan.age_at_maturity_check(75).is_ok());
+        // Err. Созаемщику 60 лет - допустимо не более 15 лет
+        loan.params.co_borrowers = vec![Borrower {
+            date_of_birth: today.checked_sub_months(Months::new(60 * 12)),
+            ..Borrower::default()
+        }];
+        assert_eq!(
+            loan.age_at_maturity_check(75).err().unwrap(),
+            AgeAtMaturity {
+                max_age: 75,
+                max_months: 179
+            }
+        );
+        // Err. Созаемщик указал только возраст: 60 + 240 / 12 = 80 лет
+        loan.params.co_borrowers = vec![Borrower {
+            age: Some(60),
+            ..Borrower::default()
+        }];
+        assert_eq!(
+            loan.age_at_maturity_check(75).err().unwrap(),
+            AgeAtMaturity {
+                max_age: 75,
+                max_months: 180
+            }
+        );
+        // Ok. 55 + 240 / 12 = 75 лет
+        loan.params.co_borrowers[0].age = Some(55);
+        assert!(loan.age_at_maturity_check(75).is_ok());
+        // Err. Возраст больше максимального
+        loan.params.co_borrowers[0].age = Some(80);
+        assert_eq!(
+            loan.age_at_maturity_check(75).err().unwrap(),
+            AgeAtMaturity {
+                max_age: 75,
+                max_months: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_affordability_check() {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.aggregates.monthly_payment = 50_000_f64;
+        // Ok. Доход не указан
+        assert!(loan.affordability_check(50_f64).is_ok());
+        assert_eq!(loan.aggregates.payment_to_income, None);
+        // Err. Дохода заемщика недостаточно
+        loan.params.borrower.income = 80_000_f64;
+        assert_eq!(
+            loan.affordability_check(50_f64).err().unwrap(),
+            Affordability
+        );
+        // Ok. Совокупный доход с созаемщиком
+        loan.params.co_borrowers = vec![Borrower {
+            income: 45_000_f64,
+            ..Borrower::default()
+        }];
+        assert!(loan.affordability_check(50_f64).is_ok());
+        assert_eq!(loan.aggregates.payment_to_income, Some(40_f64));
+    }
+
+    #[test]
+    fn test_annuity_payment() {
+        assert_eq!(annuity_payment(4_000_000_f64, SALARY, 240), 33458_f64);
+        assert_eq!(annuity_payment(4_000_000_f64, SALARY, 360), 29351_f64);
+        // Ok. Нулевая ставка - равными долями
+        assert_eq!(annuity_payment(700_000_f64, 0_f64, 120), 5834_f64);
+        // Ok. Без срока - вся сумма
+        assert_eq!(annuity_payment(700_000_f64, SALARY, 0), 700_000_f64);
+    }
+
     #[test]
     fn test_overpayment_calc() {
         let mut loan: Mortgage = Mortgage::default();
@@ -244,6 +1288,219 @@ mod tests {
         assert_eq!(loan.aggregates.overpayment, 5000_f64)
     }
 
+    #[test]
+    fn test_program_kind() {
+        let actual: Program = Program::from(ProgramKind::Military);
+        assert_eq!(actual.military, Some(true));
+        assert_eq!(actual.salary, None);
+        assert_eq!(actual.base, None);
+        assert_eq!(actual.kind(), Some(ProgramKind::Military));
+        assert_eq!(Program::default().kind(), None);
+    }
+
+    #[test]
+    fn test_execute() {
+        // Ok. Полный расчет кредита
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 100_f64;
+        loan.params.initial_payment = 30_f64;
+        loan.params.months = 12;
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        assert_eq!(loan.aggregates.monthly_payment, 7_f64);
+        assert_eq!(loan.total_cost(), 84_f64);
+        assert!(loan.schedule.is_empty());
+        // Err. Первоначальный взнос меньше минимально допустимого
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 100_f64;
+        loan.params.initial_payment = 10_f64;
+        loan.params.months = 12;
+        assert_eq!(
+            loan.execute(&Catalog::default()).err().unwrap(),
+            MinInitialPayment
+        );
+        // Err. Программы нет в каталоге
+        let mut catalog: Catalog = Catalog::default();
+        catalog.programs.remove(&ProgramKind::Base);
+        assert_eq!(loan.execute(&catalog).err().unwrap(), ProgramUnavailable);
+        // Err. Заемщик не соответствует условиям военной ипотеки
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Military),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 100_f64;
+        loan.params.initial_payment = 30_f64;
+        loan.params.months = 12;
+        assert_eq!(
+            loan.execute(&Catalog::default()).err().unwrap(),
+            MilitaryServiceRequired
+        );
+        // Err. Условиям соответствует только созаемщик: проверяется основной заемщик
+        loan.params.co_borrowers = vec![Borrower {
+            military_service: true,
+            ..Borrower::default()
+        }];
+        assert_eq!(
+            loan.execute(&Catalog::default()).err().unwrap(),
+            MilitaryServiceRequired
+        );
+        // Ok. Заемщик - военнослужащий, созаемщик условиям не соответствует
+        loan.params.borrower.military_service = true;
+        loan.params.co_borrowers[0].military_service = false;
+        assert!(loan.execute(&Catalog::default()).is_ok());
+    }
+
+    #[test]
+    fn test_schedule_calc() {
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 1500_f64;
+        loan.params.initial_payment = 300_f64;
+        loan.params.months = 12;
+        // Ok. Погашение субсидией на 6 месяце пересчитывает график
+        loan.params.contributions = vec![contribution(Source::StateSubsidy, 450_f64, Some(6))];
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        assert_eq!(loan.aggregates.loan_sum, 1200_f64);
+        assert_eq!(loan.schedule.len(), 12);
+        assert_eq!(loan.schedule[5].subsidy, 450_f64);
+        assert!(loan.schedule[6].payment < loan.schedule[4].payment);
+        assert!(loan.schedule.last().unwrap().balance.abs() < 1e-9);
+        // Ok. Переплата и вычет - по графику с субсидией
+        let interest: f64 = loan.schedule.iter().map(|p| p.interest).sum();
+        assert!((loan.aggregates.overpayment - interest).abs() < 1e-9);
+        assert!(loan.aggregates.overpayment < loan.aggregates.monthly_payment * 12_f64 - 1200_f64);
+        loan.params.taxable_income = Some(1_000_000_f64);
+        loan.deduction_calc();
+        let deduction: Deduction = loan.deduction.clone().unwrap();
+        assert!((deduction.net_overpayment - (interest - deduction.total)).abs() < 1e-9);
+        // Ok. Субсидия погашает кредит досрочно - дата последнего платежа по графику
+        loan.params.issue_date = NaiveDate::from_ymd_opt(2024, 1, 31);
+        loan.params.contributions = vec![contribution(Source::StateSubsidy, 1200_f64, Some(6))];
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        assert_eq!(loan.schedule.len(), 6);
+        assert_eq!(loan.aggregates.last_payment_date, "2024-07-31");
+    }
+
+    #[test]
+    fn test_contributions_check() {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.months = 12;
+        // Ok. Взнос в первоначальный и погашение в конце срока
+        loan.params.contributions = vec![
+            contribution(Source::MaternityCapital, 10_f64, None),
+            contribution(Source::StateSubsidy, 10_f64, Some(12)),
+        ];
+        assert!(loan.contributions_check().is_ok());
+        // Err. Отрицательная сумма
+        loan.params.contributions = vec![contribution(Source::Employer, -10_f64, None)];
+        assert_eq!(
+            loan.contributions_check().err().unwrap(),
+            InvalidContribution
+        );
+        // Err. Погашение в месяц выдачи
+        loan.params.contributions = vec![contribution(Source::StateSubsidy, 10_f64, Some(0))];
+        assert_eq!(
+            loan.contributions_check().err().unwrap(),
+            InvalidContribution
+        );
+        // Err. Погашение после срока кредита
+        loan.params.contributions = vec![contribution(Source::StateSubsidy, 10_f64, Some(13))];
+        assert_eq!(
+            loan.contributions_check().err().unwrap(),
+            InvalidContribution
+        );
+    }
+
+    #[test]
+    fn test_payment_day_check() {
+        let mut loan: Mortgage = Mortgage::default();
+        // Ok. День платежа не указан
+        assert!(loan.payment_day_check().is_ok());
+        // Ok. Последний день месяца
+        loan.params.payment_day = Some(31);
+        assert!(loan.payment_day_check().is_ok());
+        // Err. Несуществующий день
+        loan.params.payment_day = Some(0);
+        assert_eq!(loan.payment_day_check().err().unwrap(), InvalidPaymentDay);
+    }
+
+    #[test]
+    fn test_day_count() {
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 1500_f64;
+        loan.params.initial_payment = 300_f64;
+        loan.params.months = 12;
+        loan.params.issue_date = NaiveDate::from_ymd_opt(2024, 1, 20);
+        loan.params.payment_day = Some(5);
+        loan.params.day_count = DayCount::ActAct;
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        // Ok. График по фактическим дням с неполным первым периодом
+        assert_eq!(loan.schedule.len(), 12);
+        assert_eq!(
+            loan.schedule[0].date,
+            NaiveDate::from_ymd_opt(2024, 2, 5).unwrap()
+        );
+        assert!((loan.schedule[0].interest - 1200_f64 * 0.1 * 16_f64 / 366_f64).abs() < 1e-9);
+        // Ok. Переплата - сумма процентов по графику
+        let interest: f64 = loan.schedule.iter().map(|p| p.interest).sum();
+        assert_eq!(loan.aggregates.overpayment, interest);
+        assert_eq!(loan.aggregates.last_payment_date, "2025-01-05");
+    }
+
+    #[test]
+    fn test_date_shift() {
+        let mut loan: Mortgage = Mortgage {
+            program: Program::from(ProgramKind::Base),
+            ..Mortgage::default()
+        };
+        loan.params.object_cost = 100_f64;
+        loan.params.initial_payment = 30_f64;
+        loan.params.months = 12;
+        loan.params.issue_date = NaiveDate::from_ymd_opt(2024, 1, 1);
+        loan.params.date_shift = DateShift::Following;
+        let catalog: Catalog = Catalog {
+            calendar: Arc::new(Calendar::parse("2025-01-01\n2025-01-02").unwrap()),
+            ..Catalog::default()
+        };
+        // Ok. Праздники 1-2 января - последний платеж в пятницу 3 января
+        assert!(loan.execute(&catalog).is_ok());
+        assert_eq!(loan.aggregates.last_payment_date, "2025-01-03");
+        assert_eq!(
+            loan.schedule()[0].date,
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deduction_calc() {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.object_cost = 5_000_000_f64;
+        loan.params.months = 240;
+        loan.aggregates.loan_sum = 4_000_000_f64;
+        loan.aggregates.rate = SALARY;
+        loan.monthly_payment_calc();
+        loan.overpayment_calc();
+        // Ok. Без дохода вычет не рассчитывается
+        loan.deduction_calc();
+        assert!(loan.deduction.is_none());
+        // Ok. Полный вычет: 260 тыс. с покупки и 390 тыс. с процентов
+        loan.params.taxable_income = Some(3_000_000_f64);
+        loan.deduction_calc();
+        let deduction: Deduction = loan.deduction.unwrap();
+        assert!((deduction.total - 650_000_f64).abs() < 1e-6);
+        assert!((deduction.net_overpayment - 3_379_920_f64).abs() < 1e-6);
+    }
+
     #[test]
     fn test_last_payment_date_calc() {
         let mut loan: Mortgage = Mortgage::default();
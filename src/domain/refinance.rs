@@ -0,0 +1,217 @@
+use crate::domain::catalog::{Catalog, ProgramRules, MAX_MONTHS};
+use crate::domain::mortgage::{Borrower, Mortgage, Program};
+use crate::errors::AppError;
+use crate::errors::AppError::{InvalidRefinance, LoanProgramEmpty};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Параметры рефинансирования.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RefinanceParams {
+    // остаток задолженности
+    pub balance: f64,
+    // оценочная стоимость объекта для расчета LTV, по умолчанию равна остатку
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_value: Option<f64>,
+    // текущая годовая ставка
+    pub rate: f64,
+    // оставшийся срок
+    #[schema(minimum = 1, maximum = 360)]
+    pub months_left: u16,
+    // срок нового кредита, по умолчанию равен оставшемуся
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(minimum = 1, maximum = 360)]
+    pub months: Option<u16>,
+    // программа нового кредита
+    pub program: Program,
+    // сведения о заемщике для проверки условий программы
+    #[serde(default)]
+    pub borrower: Borrower,
+    // расходы на рефинансирование
+    #[serde(default)]
+    pub fees: f64,
+}
+
+/// Результат рефинансирования.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Refinance {
+    pub current: Mortgage,
+    pub new: Mortgage,
+    // экономия на ежемесячном платеже
+    pub monthly_saving: f64,
+    // экономия на процентах с учетом расходов, отрицательная - потеря
+    pub interest_saved: f64,
+    // месяц окупаемости расходов
+    pub break_even_month: Option<u16>,
+}
+
+impl RefinanceParams {
+    /// Проверка параметров рефинансирования: сроки от 1 месяца до максимального срока.
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.balance <= 0_f64
+            || self.rate <= 0_f64
+            || !(1..=MAX_MONTHS).contains(&self.months_left)
+            || self
+                .months
+                .is_some_and(|months| !(1..=MAX_MONTHS).contains(&months))
+            || self.fees < 0_f64
+            || self
+                .property_value
+                .is_some_and(|value| value < self.balance)
+        {
+            return Err(InvalidRefinance);
+        }
+
+        Ok(())
+    }
+}
+
+/// Расчет рефинансирования действующего кредита.
+/// Заемщик проверяется по условиям программы нового кредита из каталога.
+pub fn refinance_calc(params: RefinanceParams, catalog: &Catalog) -> Result<Refinance, AppError> {
+    params.check()?;
+    // действующий кредит на оставшийся срок
+    let mut current: Mortgage = Mortgage::default();
+    current.params.object_cost = params.balance;
+    current.params.months = params.months_left;
+    current.loan_sum_calc();
+    current.aggregates.rate = params.rate;
+    current.aggregates.rate_breakdown.base_rate = params.rate;
+    current.monthly_payment_calc();
+    current.overpayment_calc();
+    current.last_payment_date_calc();
+    // новый кредит по выбранной программе
+    let mut new: Mortgage = Mortgage {
+        program: params.program,
+        ..Mortgage::default()
+    };
+    new.params.object_cost = params.property_value.unwrap_or(params.balance);
+    new.params.initial_payment = new.params.object_cost - params.balance;
+    new.params.months = params.months.unwrap_or(params.months_left);
+    new.params.borrower = params.borrower;
+    new.loan_program_check()?;
+    let rules: &ProgramRules = catalog.rules(new.program.kind().ok_or(LoanProgramEmpty)?)?;
+    rules
+        .eligibility
+        .check(&new.params.borrower, new.issue_date())?;
+    new.loan_sum_calc();
+    new.loan_limits_check(rules)?;
+    new.rate_calc(rules)?;
+    new.monthly_payment_calc();
+    new.overpayment_calc();
+    new.last_payment_date_calc();
+
+    let monthly_saving: f64 = current.aggregates.monthly_payment - new.aggregates.monthly_payment;
+    let interest_saved: f64 =
+        current.aggregates.overpayment - new.aggregates.overpayment - params.fees;
+    // окупаемость в пределах срока обоих кредитов
+    let horizon: u16 = current.params.months.min(new.params.months);
+    let break_even_month: Option<u16> = if monthly_saving > 0_f64 {
+        let month: f64 = (params.fees / monthly_saving).ceil();
+        (month <= horizon as f64).then_some(month as u16)
+    } else {
+        None
+    };
+
+    Ok(Refinance {
+        current,
+        new,
+        monthly_saving,
+        interest_saved,
+        break_even_month,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AppError::SalaryClientRequired;
+
+    fn params() -> RefinanceParams {
+        RefinanceParams {
+            balance: 4_000_000_f64,
+            property_value: Some(6_000_000_f64),
+            rate: 12_f64,
+            months_left: 240,
+            months: None,
+            program: Program {
+                salary: Some(true),
+                ..Program::default()
+            },
+            borrower: Borrower {
+                salary_client: true,
+                ..Borrower::default()
+            },
+            fees: 100_000_f64,
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        // Ok. Корректные параметры
+        assert!(params().check().is_ok());
+        // Err. Нулевой срок нового кредита
+        let mut p: RefinanceParams = params();
+        p.months = Some(0);
+        assert_eq!(p.check().err().unwrap(), InvalidRefinance);
+        // Ok. Оставшийся срок больше 255 месяцев
+        let mut p: RefinanceParams = params();
+        p.months_left = MAX_MONTHS;
+        assert!(p.check().is_ok());
+        // Err. Срок нового кредита больше максимального
+        let mut p: RefinanceParams = params();
+        p.months = Some(MAX_MONTHS + 1);
+        assert_eq!(p.check().err().unwrap(), InvalidRefinance);
+        // Err. Отрицательные расходы
+        let mut p: RefinanceParams = params();
+        p.fees = -1_f64;
+        assert_eq!(p.check().err().unwrap(), InvalidRefinance);
+        // Err. Стоимость объекта меньше остатка
+        let mut p: RefinanceParams = params();
+        p.property_value = Some(1_f64);
+        assert_eq!(p.check().err().unwrap(), InvalidRefinance);
+    }
+
+    #[test]
+    fn test_refinance_calc() {
+        // Ok. Снижение ставки с 12% до 8%
+        let actual: Refinance = refinance_calc(params(), &Catalog::default()).unwrap();
+        assert_eq!(actual.current.aggregates.monthly_payment, 44044_f64);
+        assert_eq!(actual.new.aggregates.rate, 8_f64);
+        assert_eq!(actual.new.aggregates.monthly_payment, 33458_f64);
+        assert_eq!(actual.monthly_saving, 10586_f64);
+        assert_eq!(actual.interest_saved, 2_440_640_f64);
+        assert_eq!(actual.break_even_month, Some(10));
+        // Ok. Кредит на 30 лет с оставшимися 300 месяцами
+        let mut p: RefinanceParams = params();
+        p.months_left = 300;
+        let actual: Refinance = refinance_calc(p, &Catalog::default()).unwrap();
+        assert_eq!(actual.current.params.months, 300);
+        assert_eq!(actual.new.params.months, 300);
+        // Ok. Без оценки объекта LTV 100% - надбавка к ставке
+        let mut p: RefinanceParams = params();
+        p.property_value = None;
+        let actual: Refinance = refinance_calc(p, &Catalog::default()).unwrap();
+        assert_eq!(actual.new.aggregates.rate, 8.5);
+        // Ok. Рост ставки - окупаемости нет
+        let mut p: RefinanceParams = params();
+        p.rate = 7_f64;
+        let actual: Refinance = refinance_calc(p, &Catalog::default()).unwrap();
+        assert!(actual.interest_saved < 0_f64);
+        assert_eq!(actual.break_even_month, None);
+        // Err. Не выбрана программа нового кредита
+        let mut p: RefinanceParams = params();
+        p.program = Program::default();
+        assert_eq!(
+            refinance_calc(p, &Catalog::default()).err().unwrap(),
+            LoanProgramEmpty
+        );
+        // Err. Заемщик не клиент зарплатного проекта
+        let mut p: RefinanceParams = params();
+        p.borrower.salary_client = false;
+        assert_eq!(
+            refinance_calc(p, &Catalog::default()).err().unwrap(),
+            SalaryClientRequired
+        );
+    }
+}
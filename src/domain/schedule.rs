@@ -0,0 +1,379 @@
+use crate::domain::calendar::{Calendar, DateShift};
+use crate::domain::mortgage::annuity_payment;
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Платеж графика.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Payment {
+    pub month: u16,
+    pub date: NaiveDate,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    // погашение за счет субсидии после платежа
+    pub subsidy: f64,
+    pub balance: f64,
+}
+
+/// Конвенция начисления процентов.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum DayCount {
+    // 1/12 годовой ставки за каждый месяц
+    #[default]
+    #[serde(rename = "monthly")]
+    Monthly,
+    // фактические дни периода / 365
+    #[serde(rename = "act_365")]
+    Act365,
+    // фактические дни периода / дни в году (365 или 366)
+    #[serde(rename = "act_act")]
+    ActAct,
+}
+
+impl DayCount {
+    /// Ставка за процентный период, в долях.
+    pub fn period_rate(&self, rate: f64, from: NaiveDate, to: NaiveDate) -> f64 {
+        match self {
+            DayCount::Monthly => rate / 100_f64 / 12_f64,
+            DayCount::Act365 => rate / 100_f64 * (to - from).num_days() as f64 / 365_f64,
+            DayCount::ActAct => {
+                // дни периода делятся по календарным годам
+                let mut fraction: f64 = 0_f64;
+                let mut start: NaiveDate = from;
+                while start < to {
+                    let end: NaiveDate = NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                        .unwrap_or(NaiveDate::MAX)
+                        .min(to);
+                    let year_days: f64 = if start.leap_year() { 366_f64 } else { 365_f64 };
+                    fraction += (end - start).num_days() as f64 / year_days;
+                    start = end;
+                }
+                rate / 100_f64 * fraction
+            }
+        }
+    }
+}
+
+/// Параметры графика платежей.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScheduleParams {
+    pub loan_sum: f64,
+    pub rate: f64,
+    pub months: u16,
+    pub monthly_payment: f64,
+    pub issue_date: NaiveDate,
+    // погашения субсидиями после выдачи: месяц и сумма
+    pub subsidies: Vec<(u16, f64)>,
+    // выборка кредита траншами: месяц и сумма, без траншей - вся сумма при выдаче
+    pub disbursements: Vec<(u16, f64)>,
+    // изменения годовой ставки после платежа: месяц и новая ставка
+    pub rate_changes: Vec<(u16, f64)>,
+    pub day_count: DayCount,
+    // день месяца для платежей, без него - день выдачи
+    pub payment_day: Option<u8>,
+    // перенос дат платежей с нерабочих дней
+    pub date_shift: DateShift,
+    pub calendar: Arc<Calendar>,
+}
+
+/// Дата платежа за месяц `month` после выдачи.
+/// Без дня платежа - через `month` месяцев от выдачи. С днем платежа первый платеж -
+/// в ближайший такой день после выдачи (неполный первый период), далее ежемесячно
+/// в этот день или в последний день месяца, если месяц короче.
+pub fn payment_date(issue_date: NaiveDate, payment_day: Option<u8>, month: u16) -> NaiveDate {
+    let Some(day) = payment_day.filter(|_| month > 0) else {
+        return issue_date
+            .checked_add_months(Months::new(month as u32))
+            .unwrap_or(NaiveDate::MAX);
+    };
+    let offset: u16 = if day_of_month(issue_date, 0, day) > issue_date {
+        0
+    } else {
+        1
+    };
+    day_of_month(issue_date, month - 1 + offset, day)
+}
+
+/// День месяца через `months` месяцев от даты, не позже последнего дня месяца.
+fn day_of_month(date: NaiveDate, months: u16, day: u8) -> NaiveDate {
+    date.with_day(1)
+        .and_then(|start| start.checked_add_months(Months::new(months as u32)))
+        .and_then(|start| (1..=day as u32).rev().find_map(|d| start.with_day(d)))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+/// Сумма событий графика в месяце.
+fn amount_at(events: &[(u16, f64)], month: u16) -> f64 {
+    events
+        .iter()
+        .filter(|(event_month, _)| *event_month == month)
+        .map(|(_, amount)| amount)
+        .sum()
+}
+
+/// График аннуитетных платежей.
+/// До выборки последнего транша уплачиваются только проценты на выбранную сумму,
+/// после - аннуитет на оставшийся срок. После погашения субсидией платеж
+/// пересчитывается на оставшийся срок, как и после изменения ставки.
+/// Последний платеж закрывает остаток задолженности. Проценты за период начисляются
+/// по конвенции `day_count` от даты предыдущего платежа (или выдачи), даты платежей
+/// переносятся с нерабочих дней по правилу `date_shift`.
+pub fn schedule(params: &ScheduleParams) -> Vec<Payment> {
+    let mut rate: f64 = params.rate;
+    let mut monthly_payment: f64 = params.monthly_payment;
+    // месяц выборки последнего транша
+    let construction_end: u16 = params
+        .disbursements
+        .iter()
+        .map(|(month, _)| *month)
+        .max()
+        .unwrap_or_default();
+    let mut balance: f64 = if params.disbursements.is_empty() {
+        params.loan_sum
+    } else {
+        amount_at(&params.disbursements, 0)
+    };
+    let mut payments: Vec<Payment> = Vec::with_capacity(params.months as usize);
+    let mut period_start: NaiveDate = params.issue_date;
+
+    for month in 1..=params.months {
+        let date: NaiveDate = params.calendar.adjust(
+            payment_date(params.issue_date, params.payment_day, month),
+            params.date_shift,
+        );
+        let interest: f64 = balance * params.day_count.period_rate(rate, period_start, date);
+        period_start = date;
+        // проценты в период строительства, последний платеж или досрочное закрытие
+        let principal: f64 = if month <= construction_end {
+            0_f64
+        } else if month == params.months {
+            balance
+        } else {
+            (monthly_payment - interest).min(balance)
+        };
+        balance -= principal;
+        // выборка транша
+        balance += amount_at(&params.disbursements, month);
+        // погашение субсидией и пересчет платежа
+        let subsidy: f64 = amount_at(&params.subsidies, month).min(balance);
+        balance -= subsidy;
+        // изменение ставки
+        let rate_change: Option<f64> = params
+            .rate_changes
+            .iter()
+            .find(|(change_month, _)| *change_month == month)
+            .map(|(_, new_rate)| *new_rate);
+        if let Some(new_rate) = rate_change {
+            rate = new_rate;
+        }
+        // пересчет платежа после полной выборки, погашения субсидией или изменения ставки
+        if month >= construction_end
+            && (month == construction_end || subsidy > 0_f64 || rate_change.is_some())
+        {
+            monthly_payment = annuity_payment(balance, rate, params.months - month);
+        }
+        payments.push(Payment {
+            month,
+            date,
+            payment: interest + principal,
+            interest,
+            principal,
+            subsidy,
+            balance,
+        });
+        if balance <= 0_f64 && month >= construction_end {
+            break;
+        }
+    }
+
+    payments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> ScheduleParams {
+        ScheduleParams {
+            loan_sum: 1200_f64,
+            rate: 12_f64,
+            months: 12,
+            monthly_payment: 107_f64,
+            issue_date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            subsidies: Vec::new(),
+            disbursements: Vec::new(),
+            rate_changes: Vec::new(),
+            day_count: DayCount::Monthly,
+            payment_day: None,
+            date_shift: DateShift::None,
+            calendar: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn test_schedule() {
+        let actual: Vec<Payment> = schedule(&params());
+        assert_eq!(actual.len(), 12);
+        // первый платеж
+        assert_eq!(actual[0].interest, 12_f64);
+        assert_eq!(actual[0].principal, 95_f64);
+        assert_eq!(actual[0].balance, 1105_f64);
+        assert_eq!(
+            actual[0].date,
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        // последний платеж закрывает остаток
+        let last: &Payment = actual.last().unwrap();
+        assert!(last.balance.abs() < 1e-9);
+        assert!(last.payment < 107_f64);
+        assert_eq!(last.date, NaiveDate::from_ymd_opt(2025, 1, 31).unwrap());
+        // проценты + тело = сумма платежей
+        let paid: f64 = actual.iter().map(|p| p.payment).sum();
+        let interest: f64 = actual.iter().map(|p| p.interest).sum();
+        assert!((paid - interest - 1200_f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_schedule_subsidy() {
+        let mut p: ScheduleParams = params();
+        p.subsidies = vec![(1, 300_f64), (1, 200_f64)];
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual.len(), 12);
+        assert_eq!(actual[0].subsidy, 500_f64);
+        assert_eq!(actual[0].balance, 605_f64);
+        // платеж пересчитан на оставшиеся 11 месяцев
+        assert_eq!(actual[1].payment, annuity_payment(605_f64, 12_f64, 11));
+        assert!(actual.last().unwrap().balance.abs() < 1e-9);
+        // субсидия больше остатка закрывает кредит
+        p.subsidies = vec![(1, 5000_f64)];
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].subsidy, 1105_f64);
+    }
+
+    #[test]
+    fn test_schedule_disbursements() {
+        let mut p: ScheduleParams = params();
+        p.disbursements = vec![(0, 600_f64), (2, 600_f64)];
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual.len(), 12);
+        // проценты на выбранную сумму в период строительства
+        assert_eq!(actual[0].payment, 6_f64);
+        assert_eq!(actual[0].principal, 0_f64);
+        assert_eq!(actual[1].payment, 6_f64);
+        assert_eq!(actual[1].balance, 1200_f64);
+        // аннуитет на оставшиеся 10 месяцев после полной выборки
+        assert_eq!(actual[2].payment, annuity_payment(1200_f64, 12_f64, 10));
+        assert!(actual.last().unwrap().balance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_schedule_rate_changes() {
+        let mut p: ScheduleParams = params();
+        p.rate_changes = vec![(6, 24_f64)];
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual.len(), 12);
+        // платеж пересчитан по новой ставке на оставшиеся 6 месяцев
+        assert_eq!(
+            actual[6].payment,
+            annuity_payment(actual[5].balance, 24_f64, 6)
+        );
+        assert!((actual[6].interest - actual[5].balance * 0.02).abs() < 1e-9);
+        assert!(actual.last().unwrap().balance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_period_rate() {
+        let from: NaiveDate = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let to: NaiveDate = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        // Ok. 1/12 годовой ставки вне зависимости от дней
+        assert_eq!(DayCount::Monthly.period_rate(12_f64, from, to), 0.01);
+        // Ok. 31 день / 365
+        assert_eq!(
+            DayCount::Act365.period_rate(36.5, from, to),
+            0.365 * 31_f64 / 365_f64
+        );
+        // Ok. 17 дней 2023 года / 365 + 14 дней високосного 2024 / 366
+        assert_eq!(
+            DayCount::ActAct.period_rate(100_f64, from, to),
+            17_f64 / 365_f64 + 14_f64 / 366_f64
+        );
+    }
+
+    #[test]
+    fn test_payment_date() {
+        let issue_date: NaiveDate = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        // Ok. Без дня платежа - день выдачи
+        assert_eq!(
+            payment_date(issue_date, None, 1),
+            NaiveDate::from_ymd_opt(2024, 2, 20).unwrap()
+        );
+        // Ok. День платежа позже дня выдачи - первый платеж в том же месяце
+        assert_eq!(
+            payment_date(issue_date, Some(25), 1),
+            NaiveDate::from_ymd_opt(2024, 1, 25).unwrap()
+        );
+        // Ok. День платежа раньше дня выдачи - первый платеж в следующем месяце
+        assert_eq!(
+            payment_date(issue_date, Some(5), 1),
+            NaiveDate::from_ymd_opt(2024, 2, 5).unwrap()
+        );
+        // Ok. Короткий месяц - последний день месяца
+        assert_eq!(
+            payment_date(issue_date, Some(31), 2),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        // Ok. Месяц 0 - дата выдачи
+        assert_eq!(payment_date(issue_date, Some(5), 0), issue_date);
+    }
+
+    #[test]
+    fn test_schedule_date_shift() {
+        let mut p: ScheduleParams = params();
+        p.calendar = Arc::new(Calendar::parse("2024-02-29").unwrap());
+        // Ok. Праздник 29.02 - на пятницу 01.03, воскресенье 31.03 - на понедельник 01.04
+        p.date_shift = DateShift::Following;
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual[0].date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(actual[1].date, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+        // Ok. В пределах месяца - на предыдущий рабочий день
+        p.date_shift = DateShift::ModifiedFollowing;
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(
+            actual[0].date,
+            NaiveDate::from_ymd_opt(2024, 2, 28).unwrap()
+        );
+        assert_eq!(
+            actual[1].date,
+            NaiveDate::from_ymd_opt(2024, 3, 29).unwrap()
+        );
+        // Ok. Проценты по фактическим дням между перенесенными датами
+        p.day_count = DayCount::Act365;
+        let actual: Vec<Payment> = schedule(&p);
+        assert!((actual[1].interest - actual[0].balance * 0.12 * 30_f64 / 365_f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_schedule_day_count() {
+        let mut p: ScheduleParams = params();
+        p.day_count = DayCount::Act365;
+        p.payment_day = Some(10);
+        let actual: Vec<Payment> = schedule(&p);
+        assert_eq!(actual.len(), 12);
+        // неполный первый период: с 31.01 по 10.02 - 10 дней
+        assert_eq!(
+            actual[0].date,
+            NaiveDate::from_ymd_opt(2024, 2, 10).unwrap()
+        );
+        assert_eq!(actual[0].interest, 1200_f64 * 0.12 * 10_f64 / 365_f64);
+        // полный период: с 10.02 по 10.03 - 29 дней
+        assert_eq!(
+            actual[1].interest,
+            actual[0].balance * 0.12 * 29_f64 / 365_f64
+        );
+        assert!(actual.last().unwrap().balance.abs() < 1e-9);
+    }
+}
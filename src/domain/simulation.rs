@@ -0,0 +1,297 @@
+use crate::domain::mortgage::Mortgage;
+use crate::domain::schedule::{schedule, Payment, ScheduleParams};
+use crate::errors::AppError;
+use crate::errors::AppError::InvalidSimulation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Максимальное количество траекторий.
+const MAX_PATHS: u32 = 100_000;
+// Шаг модели в годах (месяц).
+const DT: f64 = 1_f64 / 12_f64;
+
+/// Стохастическая модель референсной ставки.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RateModel {
+    // модель Васичека с возвратом к среднему
+    Vasicek {
+        // долгосрочное среднее, %
+        mean: f64,
+        // скорость возврата к среднему, в год
+        reversion: f64,
+        // волатильность, п.п. в год
+        volatility: f64,
+    },
+}
+
+/// Параметры симуляции плавающей ставки.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimulationParams {
+    // id расчета в кэше
+    pub id: u32,
+    pub model: RateModel,
+    // надбавка к референсной ставке, п.п.
+    pub margin: f64,
+    // период пересмотра ставки, мес.
+    #[serde(default = "default_reset_months")]
+    pub reset_months: u16,
+    pub paths: u32,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// Перцентили распределения.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Percentiles {
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+/// Результат симуляции.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Simulation {
+    pub id: u32,
+    pub paths: u32,
+    pub overpayment: Percentiles,
+    pub max_monthly_payment: Percentiles,
+    // вероятность превышения допустимой доли платежа в доходе
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breach_probability: Option<f64>,
+}
+
+/// Период пересмотра ставки по умолчанию.
+fn default_reset_months() -> u16 {
+    12
+}
+
+impl SimulationParams {
+    /// Проверка параметров симуляции.
+    pub fn check(&self) -> Result<(), AppError> {
+        let RateModel::Vasicek {
+            mean,
+            reversion,
+            volatility,
+        } = self.model;
+        if self.paths == 0
+            || self.paths > MAX_PATHS
+            || self.reset_months == 0
+            || !mean.is_finite()
+            || !self.margin.is_finite()
+            || self.margin < 0_f64
+            || !(0_f64..=100_f64).contains(&reversion)
+            || !(0_f64..=100_f64).contains(&volatility)
+        {
+            return Err(InvalidSimulation);
+        }
+
+        Ok(())
+    }
+}
+
+impl RateModel {
+    /// Траектория ставки по месяцам от начального значения.
+    /// Ставка не опускается ниже нуля.
+    pub fn path(&self, initial: f64, months: u16, rng: &mut impl Rng) -> Vec<f64> {
+        let RateModel::Vasicek {
+            mean,
+            reversion,
+            volatility,
+        } = *self;
+        let mut rate: f64 = initial;
+        (0..months)
+            .map(|_| {
+                rate += reversion * (mean - rate) * DT + volatility * DT.sqrt() * normal(rng);
+                rate = rate.max(0_f64);
+                rate
+            })
+            .collect()
+    }
+}
+
+/// Стандартное нормальное распределение (преобразование Бокса-Мюллера).
+fn normal(rng: &mut impl Rng) -> f64 {
+    let u: f64 = 1_f64 - rng.gen::<f64>();
+    let v: f64 = rng.gen::<f64>();
+    (-2_f64 * u.ln()).sqrt() * (2_f64 * std::f64::consts::PI * v).cos()
+}
+
+/// Перцентиль отсортированной выборки (ближайший ранг).
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank: usize = ((p / 100_f64) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Перцентили выборки.
+fn percentiles(mut values: Vec<f64>) -> Percentiles {
+    values.sort_by(f64::total_cmp);
+    Percentiles {
+        p5: percentile(&values, 5_f64),
+        p50: percentile(&values, 50_f64),
+        p95: percentile(&values, 95_f64),
+    }
+}
+
+/// Симуляция кредита с плавающей ставкой Монте-Карло.
+/// Ставка кредита - референсная ставка с надбавкой, пересматривается каждые `reset_months`,
+/// платеж пересчитывается по графику платежей кредита. Начальная референсная ставка -
+/// ставка кредита за вычетом надбавки. Траектории считаются параллельно,
+/// каждая со своим seed, поэтому результат не зависит от количества потоков.
+pub fn simulation_calc(
+    loan: &Mortgage,
+    params: &SimulationParams,
+    max_payment_to_income: f64,
+) -> Result<Simulation, AppError> {
+    params.check()?;
+    let months: u16 = loan.params.months;
+    let initial: f64 = loan.aggregates.rate - params.margin;
+    let base: ScheduleParams = loan.schedule_params();
+
+    let outcomes: Vec<(f64, f64)> = (0..params.paths)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng: StdRng = StdRng::seed_from_u64(params.seed.wrapping_add(i as u64));
+            let path: Vec<f64> = params.model.path(initial, months, &mut rng);
+            let payments: Vec<Payment> = schedule(&ScheduleParams {
+                rate_changes: (params.reset_months..months)
+                    .step_by(params.reset_months as usize)
+                    .map(|month| (month, path[month as usize - 1] + params.margin))
+                    .collect(),
+                ..base.clone()
+            });
+            (
+                payments.iter().map(|p| p.interest).sum(),
+                payments.iter().map(|p| p.payment).fold(0_f64, f64::max),
+            )
+        })
+        .collect();
+
+    let income: f64 = loan.income();
+    let breach_probability: Option<f64> = (income > 0_f64).then(|| {
+        outcomes
+            .iter()
+            .filter(|(_, max_payment)| max_payment / income * 100_f64 > max_payment_to_income)
+            .count() as f64
+            / params.paths as f64
+    });
+    let (overpayments, max_payments): (Vec<f64>, Vec<f64>) = outcomes.into_iter().unzip();
+
+    Ok(Simulation {
+        id: params.id,
+        paths: params.paths,
+        overpayment: percentiles(overpayments),
+        max_monthly_payment: percentiles(max_payments),
+        breach_probability,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loan() -> Mortgage {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.months = 240;
+        loan.aggregates.loan_sum = 4_000_000_f64;
+        loan.aggregates.rate = 8_f64;
+        loan.monthly_payment_calc();
+        loan.params.borrower.income = 70_000_f64;
+        loan
+    }
+
+    fn params(volatility: f64) -> SimulationParams {
+        SimulationParams {
+            id: 0,
+            model: RateModel::Vasicek {
+                mean: 6_f64,
+                reversion: 0.5,
+                volatility,
+            },
+            margin: 2_f64,
+            reset_months: 12,
+            paths: 200,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        // Ok. Корректные параметры
+        assert!(params(1_f64).check().is_ok());
+        // Err. Нет траекторий
+        let mut p: SimulationParams = params(1_f64);
+        p.paths = 0;
+        assert_eq!(p.check().err().unwrap(), InvalidSimulation);
+        // Err. Отрицательная волатильность
+        assert_eq!(params(-1_f64).check().err().unwrap(), InvalidSimulation);
+        // Err. Отрицательная надбавка
+        let mut p: SimulationParams = params(1_f64);
+        p.margin = -1_f64;
+        assert_eq!(p.check().err().unwrap(), InvalidSimulation);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let actual: Percentiles = percentiles((1..=100).rev().map(f64::from).collect());
+        assert_eq!(
+            actual,
+            Percentiles {
+                p5: 5_f64,
+                p50: 50_f64,
+                p95: 95_f64
+            }
+        );
+    }
+
+    #[test]
+    fn test_simulation_calc() {
+        let loan: Mortgage = loan();
+        // Ok. Без волатильности и при среднем, равном начальной ставке, - фиксированная ставка
+        let actual: Simulation = simulation_calc(&loan, &params(0_f64), 50_f64).unwrap();
+        assert_eq!(actual.max_monthly_payment.p5, 33458_f64);
+        assert_eq!(actual.max_monthly_payment.p95, 33458_f64);
+        assert_eq!(actual.breach_probability, Some(0_f64));
+        // Ok. Детерминированный результат при одном seed
+        let actual: Simulation = simulation_calc(&loan, &params(2_f64), 50_f64).unwrap();
+        assert_eq!(
+            actual,
+            simulation_calc(&loan, &params(2_f64), 50_f64).unwrap()
+        );
+        assert!(actual.overpayment.p5 < actual.overpayment.p50);
+        assert!(actual.overpayment.p50 < actual.overpayment.p95);
+        assert!(actual.max_monthly_payment.p95 > 33458_f64);
+        assert!(actual.breach_probability.unwrap() > 0_f64);
+    }
+
+    #[test]
+    fn test_simulation_calc_zero_rate() {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.months = 120;
+        loan.params.borrower.income = 30_000_f64;
+        loan.aggregates.loan_sum = 700_000_f64;
+        loan.aggregates.rate = 8_f64;
+        loan.monthly_payment_calc();
+        // ставка сразу опускается до нуля и остается на нем
+        let params: SimulationParams = SimulationParams {
+            model: RateModel::Vasicek {
+                mean: 0_f64,
+                reversion: 100_f64,
+                volatility: 0_f64,
+            },
+            margin: 0_f64,
+            ..params(0_f64)
+        };
+        let actual: Simulation = simulation_calc(&loan, &params, 50_f64).unwrap();
+        // Ok. Максимальный платеж - в первый год по исходной ставке, без погашения всего остатка
+        assert_eq!(
+            actual.max_monthly_payment.p50,
+            loan.aggregates.monthly_payment
+        );
+        assert!(actual.overpayment.p50 < loan.aggregates.monthly_payment * 120_f64 - 700_000_f64);
+        assert_eq!(actual.breach_probability, Some(0_f64));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::domain::mortgage::Mortgage;
+use crate::domain::schedule::{schedule, Payment, ScheduleParams};
+use crate::errors::AppError;
+use crate::errors::AppError::InvalidStress;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Сценарий роста ставки.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Scenario {
+    // рост ставки, п.п.
+    pub shock: f64,
+    // год, после которого меняется ставка
+    pub after_year: u8,
+}
+
+/// Параметры стресс-теста рассчитанного кредита.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StressParams {
+    // id расчета в кэше
+    pub id: u32,
+    pub scenarios: Vec<Scenario>,
+}
+
+/// Результат сценария.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StressOutcome {
+    pub scenario: Scenario,
+    pub rate: f64,
+    // платеж после роста ставки
+    pub monthly_payment: f64,
+    pub overpayment: f64,
+    // доля платежа в совокупном доходе заемщиков, %
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_to_income: Option<f64>,
+    // превышение допустимой доли платежа в доходе
+    pub breach: bool,
+}
+
+/// Результат стресс-теста.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Stress {
+    pub id: u32,
+    pub scenarios: Vec<StressOutcome>,
+}
+
+impl StressParams {
+    /// Проверка сценариев: ставка меняется после первого года и до конца срока кредита
+    /// и остается положительной. Изменение ставки с выдачи - это другая ставка кредита,
+    /// а не сценарий роста.
+    pub fn check(&self, months: u16, rate: f64) -> Result<(), AppError> {
+        if self.scenarios.is_empty()
+            || self.scenarios.iter().any(|s| {
+                !s.shock.is_finite()
+                    || rate + s.shock <= 0_f64
+                    || s.after_year == 0
+                    || s.after_year as u16 * 12 >= months
+            })
+        {
+            return Err(InvalidStress);
+        }
+
+        Ok(())
+    }
+}
+
+/// Стресс-тест кредита: платеж и переплата при росте ставки после заданного года.
+/// Переплата считается по графику платежей, превышение - по порогу доли платежа в доходе.
+/// Если кредит по графику погашен до изменения ставки (субсидией), сценарий некорректен.
+pub fn stress_calc(
+    loan: &Mortgage,
+    params: &StressParams,
+    max_payment_to_income: f64,
+) -> Result<Stress, AppError> {
+    params.check(loan.params.months, loan.aggregates.rate)?;
+    let income: f64 = loan.income();
+
+    let scenarios: Vec<StressOutcome> = params
+        .scenarios
+        .iter()
+        .map(|scenario| {
+            let month: u16 = scenario.after_year as u16 * 12;
+            let rate: f64 = loan.aggregates.rate + scenario.shock;
+            let payments: Vec<Payment> = schedule(&ScheduleParams {
+                rate_changes: vec![(month, rate)],
+                ..loan.schedule_params()
+            });
+            let monthly_payment: f64 = payments
+                .get(month as usize)
+                .map(|p| p.payment)
+                .ok_or(InvalidStress)?;
+            let payment_to_income: Option<f64> =
+                (income > 0_f64).then(|| monthly_payment / income * 100_f64);
+            Ok(StressOutcome {
+                scenario: scenario.clone(),
+                rate,
+                monthly_payment,
+                overpayment: payments.iter().map(|p| p.interest).sum(),
+                payment_to_income,
+                breach: payment_to_income.is_some_and(|pti| pti > max_payment_to_income),
+            })
+        })
+        .collect::<Result<_, AppError>>()?;
+
+    Ok(Stress {
+        id: params.id,
+        scenarios,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::catalog::Source;
+    use crate::domain::mortgage::{annuity_payment, Contribution};
+
+    fn loan() -> Mortgage {
+        let mut loan: Mortgage = Mortgage::default();
+        loan.params.months = 240;
+        loan.aggregates.loan_sum = 4_000_000_f64;
+        loan.aggregates.rate = 8_f64;
+        loan.monthly_payment_calc();
+        loan.params.borrower.income = 100_000_f64;
+        loan
+    }
+
+    fn params(shock: f64, after_year: u8) -> StressParams {
+        StressParams {
+            id: 0,
+            scenarios: vec![Scenario { shock, after_year }],
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        // Ok. Рост ставки после 3 года
+        assert!(params(1_f64, 3).check(240, 8_f64).is_ok());
+        // Ok. Снижение ставки
+        assert!(params(-2_f64, 3).check(240, 8_f64).is_ok());
+        // Err. Нет сценариев
+        let p: StressParams = StressParams::default();
+        assert_eq!(p.check(240, 8_f64).err().unwrap(), InvalidStress);
+        // Err. Год за пределами срока
+        assert_eq!(
+            params(1_f64, 20).check(240, 8_f64).err().unwrap(),
+            InvalidStress
+        );
+        // Err. Рост ставки с выдачи
+        assert_eq!(
+            params(1_f64, 0).check(240, 8_f64).err().unwrap(),
+            InvalidStress
+        );
+        // Err. Ставка после снижения не положительная
+        assert_eq!(
+            params(-8_f64, 3).check(240, 8_f64).err().unwrap(),
+            InvalidStress
+        );
+    }
+
+    #[test]
+    fn test_stress_calc() {
+        let loan: Mortgage = loan();
+        // Ok. Без роста ставки платеж не меняется
+        let actual: Stress = stress_calc(&loan, &params(0_f64, 3), 50_f64).unwrap();
+        assert_eq!(actual.scenarios[0].monthly_payment, 33458_f64);
+        assert!(!actual.scenarios[0].breach);
+        // Ok. +5 п.п. после 3 года - платеж по новой ставке на остаток
+        let actual: Stress = stress_calc(&loan, &params(5_f64, 3), 50_f64).unwrap();
+        let outcome: &StressOutcome = &actual.scenarios[0];
+        let balance: f64 = loan.schedule()[35].balance;
+        assert_eq!(outcome.rate, 13_f64);
+        assert_eq!(
+            outcome.monthly_payment,
+            annuity_payment(balance, 13_f64, 204)
+        );
+        assert!(outcome.overpayment > loan.aggregates.monthly_payment * 240_f64 - 4_000_000_f64);
+        assert!(outcome.payment_to_income.unwrap() > 45_f64);
+        // Ok. Превышение порога доли платежа в доходе
+        let actual: Stress = stress_calc(&loan, &params(5_f64, 3), 40_f64).unwrap();
+        assert!(actual.scenarios[0].breach);
+        // Err. Кредит погашен субсидией до изменения ставки
+        let mut loan: Mortgage = loan;
+        loan.params.contributions = vec![Contribution {
+            source: Source::StateSubsidy,
+            amount: 4_000_000_f64,
+            month: Some(24),
+        }];
+        assert_eq!(
+            stress_calc(&loan, &params(5_f64, 3), 50_f64).err().unwrap(),
+            InvalidStress
+        );
+    }
+}
@@ -1,12 +1,33 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use utoipa::ToSchema;
 
 /// Переназначение Result для ответов хендлеров.
 pub type Result<T, E = AppError> = core::result::Result<T, E>;
 
+/// Тело ответа с ошибкой.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    // текст ошибки
+    #[schema(example = "choose credit program")]
+    pub error: String,
+    // id запроса (`X-Request-Id`) для поиска в логах
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+tokio::task_local! {
+    /// Id обрабатываемого запроса, задается middleware.
+    pub static REQUEST_ID: String;
+}
+
+/// Вариант ошибки в расширениях http-ответа для метрик.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorKind(pub &'static str);
+
 /// Ошибки сервиса.
 #[derive(Debug, PartialEq, Error)]
 pub enum AppError {
@@ -22,6 +43,122 @@ pub enum AppError {
     // пустой кэш
     #[error("empty cache")]
     EmptyCache,
+    // размер пакета больше допустимого
+    #[error("the batch size exceeds the limit of {max_size}")]
+    BatchTooLarge { max_size: usize },
+    // строка потокового запроса больше допустимого размера
+    #[error("the line exceeds the limit of {max_size} bytes")]
+    LineTooLarge { max_size: usize },
+    // некорректные диапазоны сетки
+    #[error("invalid grid ranges")]
+    InvalidGrid,
+    // некорректные параметры рефинансирования
+    #[error("invalid refinancing parameters")]
+    InvalidRefinance,
+    // программа отсутствует в каталоге
+    #[error("credit program is unavailable")]
+    ProgramUnavailable,
+    // заемщик не военнослужащий
+    #[error("the borrower must have military service status")]
+    MilitaryServiceRequired,
+    // заемщик не клиент зарплатного проекта
+    #[error("the borrower must be a salary project client")]
+    SalaryClientRequired,
+    // недостаточно детей для программы
+    #[error("the borrower does not have enough children")]
+    NotEnoughChildren,
+    // возраст заемщика вне допустимого диапазона
+    #[error("the borrower age is out of the allowed range")]
+    BorrowerAge,
+    // регион не входит в программу
+    #[error("the region is not covered by the program")]
+    RegionUnavailable,
+    // доли заемщиков в сумме не равны 100%
+    #[error("borrower shares must sum to 100")]
+    InvalidShares,
+    // кредит не погашается до максимального возраста заемщика
+    #[error("the loan must mature before the oldest borrower turns {max_age}, maximum term is {max_months} months")]
+    AgeAtMaturity { max_age: u8, max_months: u32 },
+    // платеж превышает допустимую долю совокупного дохода
+    #[error("the monthly payment exceeds the allowed share of income")]
+    Affordability,
+    // опция недоступна в программе
+    #[error("the rate option is not available for the program")]
+    OptionUnavailable,
+    // сумма кредита ниже лимита программы
+    #[error("the loan amount should be at least {min_loan}")]
+    LoanBelowLimit { min_loan: f64 },
+    // сумма кредита выше лимита программы
+    #[error("the loan amount exceeds the program limit of {max_loan}")]
+    LoanAboveLimit { max_loan: f64 },
+    // некорректный график выборки траншей
+    #[error("disbursements must sum to the loan amount within the term")]
+    InvalidDisbursement,
+    // некорректные взносы
+    #[error("contributions must be non-negative and repaid within the term")]
+    InvalidContribution,
+    // срок кредита вне допустимого диапазона
+    #[error("the term must be between 1 and 360 months")]
+    InvalidTerm,
+    // некорректный день платежа
+    #[error("payment day must be between 1 and 31")]
+    InvalidPaymentDay,
+    // некорректные сценарии стресс-теста
+    #[error("invalid stress scenarios")]
+    InvalidStress,
+    // некорректные параметры симуляции
+    #[error("invalid simulation parameters")]
+    InvalidSimulation,
+    // расчет не найден в кэше
+    #[error("loan not found")]
+    LoanNotFound,
+    // запрос не обработан за отведенное время
+    #[error("the request timed out after {secs} s")]
+    RequestTimeout { secs: u64 },
+    // нет или неверный токен администратора
+    #[error("invalid admin token")]
+    Unauthorized,
+    // каталог программ не перезагружен
+    #[error("catalog reload failed: {0}")]
+    ReloadFailed(String),
+}
+
+impl AppError {
+    /// Название варианта ошибки для метрик и логов.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::LoanProgramEmpty => "loan_program_empty",
+            AppError::LoanProgramMoreThanOne => "loan_program_more_than_one",
+            AppError::MinInitialPayment => "min_initial_payment",
+            AppError::EmptyCache => "empty_cache",
+            AppError::BatchTooLarge { .. } => "batch_too_large",
+            AppError::LineTooLarge { .. } => "line_too_large",
+            AppError::InvalidGrid => "invalid_grid",
+            AppError::InvalidRefinance => "invalid_refinance",
+            AppError::ProgramUnavailable => "program_unavailable",
+            AppError::MilitaryServiceRequired => "military_service_required",
+            AppError::SalaryClientRequired => "salary_client_required",
+            AppError::NotEnoughChildren => "not_enough_children",
+            AppError::BorrowerAge => "borrower_age",
+            AppError::RegionUnavailable => "region_unavailable",
+            AppError::InvalidShares => "invalid_shares",
+            AppError::AgeAtMaturity { .. } => "age_at_maturity",
+            AppError::Affordability => "affordability",
+            AppError::OptionUnavailable => "option_unavailable",
+            AppError::LoanBelowLimit { .. } => "loan_below_limit",
+            AppError::LoanAboveLimit { .. } => "loan_above_limit",
+            AppError::InvalidDisbursement => "invalid_disbursement",
+            AppError::InvalidContribution => "invalid_contribution",
+            AppError::InvalidTerm => "invalid_term",
+            AppError::InvalidPaymentDay => "invalid_payment_day",
+            AppError::InvalidStress => "invalid_stress",
+            AppError::InvalidSimulation => "invalid_simulation",
+            AppError::LoanNotFound => "loan_not_found",
+            AppError::RequestTimeout { .. } => "request_timeout",
+            AppError::Unauthorized => "unauthorized",
+            AppError::ReloadFailed(_) => "reload_failed",
+        }
+    }
 }
 
 /// Имплементация для Axum Response.
@@ -31,11 +168,42 @@ impl IntoResponse for AppError {
             AppError::LoanProgramEmpty
             | AppError::LoanProgramMoreThanOne
             | AppError::MinInitialPayment
-            | AppError::EmptyCache => (StatusCode::BAD_REQUEST, self.to_string()),
+            | AppError::EmptyCache
+            | AppError::InvalidGrid
+            | AppError::InvalidRefinance
+            | AppError::ProgramUnavailable
+            | AppError::MilitaryServiceRequired
+            | AppError::SalaryClientRequired
+            | AppError::NotEnoughChildren
+            | AppError::BorrowerAge
+            | AppError::RegionUnavailable
+            | AppError::InvalidShares
+            | AppError::AgeAtMaturity { .. }
+            | AppError::Affordability
+            | AppError::OptionUnavailable
+            | AppError::LoanBelowLimit { .. }
+            | AppError::LoanAboveLimit { .. }
+            | AppError::InvalidDisbursement
+            | AppError::InvalidContribution
+            | AppError::InvalidPaymentDay
+            | AppError::InvalidTerm
+            | AppError::InvalidStress
+            | AppError::InvalidSimulation => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::LoanNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::BatchTooLarge { .. } | AppError::LineTooLarge { .. } => {
+                (StatusCode::PAYLOAD_TOO_LARGE, self.to_string())
+            }
+            AppError::RequestTimeout { .. } => (StatusCode::REQUEST_TIMEOUT, self.to_string()),
+            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AppError::ReloadFailed(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
-        let body = Json(json!({
-            "error": err_msg,
-        }));
-        (status, body).into_response()
+        let kind: ErrorKind = ErrorKind(self.kind());
+        let body = Json(ErrorResponse {
+            error: err_msg,
+            request_id: REQUEST_ID.try_with(String::clone).ok(),
+        });
+        let mut response: Response = (status, body).into_response();
+        response.extensions_mut().insert(kind);
+        response
     }
 }
@@ -1,4 +1,5 @@
 use crate::server::execute;
+use std::process::ExitCode;
 
 mod adapter;
 mod config;
@@ -8,6 +9,13 @@ mod schema;
 mod server;
 
 #[tokio::main]
-async fn main() {
-    execute().await
+async fn main() -> ExitCode {
+    match execute().await {
+        Ok(()) => ExitCode::SUCCESS,
+        // ошибки запуска без паники и backtrace, логирование может быть еще не настроено
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
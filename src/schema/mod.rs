@@ -1,17 +1,58 @@
-use crate::domain::mortgage::{Mortgage, Program};
+use crate::domain::calendar::DateShift;
+use crate::domain::catalog::RateOption;
+use crate::domain::mortgage::{
+    is_default, Borrower, Contribution, Disbursement, Mortgage, Program, ProgramKind,
+};
+use crate::domain::schedule::DayCount;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub mod v2;
 
 /// Запрос.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
 pub struct Request {
     pub object_cost: f64,
     pub initial_payment: f64,
     pub months: u8,
     pub program: Program,
+    // годовой облагаемый доход для расчета имущественного вычета
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taxable_income: Option<f64>,
+    // прочие источники первоначального взноса и погашения
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributions: Vec<Contribution>,
+    // сведения о заемщике для проверки условий программы
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub borrower: Borrower,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_borrowers: Vec<Borrower>,
+    // дополнительные опции, изменяющие ставку
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<RateOption>,
+    // комбинированный расчет: часть сверх лимита по другой программе
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub combined: bool,
+    // выборка кредита траншами при строительстве
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disbursements: Vec<Disbursement>,
+    // дата выдачи, по умолчанию - текущая дата
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_date: Option<NaiveDate>,
+    // день месяца для платежей, по умолчанию - день выдачи
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_day: Option<u8>,
+    // конвенция начисления процентов
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub day_count: DayCount,
+    // перенос дат платежей с нерабочих дней
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub date_shift: DateShift,
 }
 
 /// Ответ кэша.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Response {
     pub id: u32,
     pub loan: Mortgage,
@@ -23,3 +64,118 @@ impl Response {
         Self { id, loan }
     }
 }
+
+/// Расчет по программе в сравнении.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComparisonItem {
+    pub program: ProgramKind,
+    pub loan: Mortgage,
+    // разница ежемесячного платежа с лучшей программой
+    pub monthly_payment_diff: f64,
+    // разница полной стоимости кредита с лучшей программой
+    pub total_cost_diff: f64,
+}
+
+/// Программа, недоступная для заданных параметров.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Ineligible {
+    pub program: ProgramKind,
+    pub error: String,
+}
+
+/// Ответ сравнения программ.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Comparison {
+    pub eligible: Vec<ComparisonItem>,
+    pub ineligible: Vec<Ineligible>,
+}
+
+/// Формат ответа сетки.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query-параметры пакетного расчета.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct BatchQuery {
+    // сохранение успешных расчетов в кэш
+    #[serde(default)]
+    pub cache: bool,
+}
+
+/// Результат расчета элемента пакета: кредит или ошибка.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchItem {
+    // индекс запроса в пакете
+    pub index: usize,
+    // id расчета в кэше, если расчет сохранен
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loan: Option<Mortgage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Query-параметры сетки.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct GridQuery {
+    #[serde(default)]
+    pub format: Format,
+}
+
+/// Статус сервиса для проб.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    NotReady,
+}
+
+/// Ответ проверки живости.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Health {
+    pub status: Status,
+}
+
+/// Ответ проверки готовности: состояние кэша и каталога программ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Readiness {
+    pub status: Status,
+    // сервис получил сигнал завершения
+    pub shutting_down: bool,
+    // количество кредитов в кэше
+    pub cached_loans: usize,
+    pub catalog_version: String,
+    // количество программ в каталоге
+    pub programs: usize,
+}
+
+/// Сведения о сборке.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BuildInfo {
+    // версия крейта
+    pub version: String,
+    // коммит git
+    pub commit: String,
+    // время сборки, RFC 3339
+    pub build_time: String,
+    // версия активного каталога программ
+    pub catalog_version: String,
+}
+
+/// Результат перезагрузки каталога программ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CatalogReload {
+    // версия каталога до перезагрузки
+    pub previous_version: String,
+    pub catalog_version: String,
+    // количество программ в каталоге
+    pub programs: usize,
+    // количество праздничных дней в календаре
+    pub holidays: usize,
+}
@@ -0,0 +1,197 @@
+use crate::domain::calendar::DateShift;
+use crate::domain::catalog::{RateBreakdown, RateOption};
+use crate::domain::deduction::Deduction;
+use crate::domain::mortgage::{
+    Aggregates, Borrower, Contribution, Disbursement, Mortgage, Params, Program, ProgramKind,
+    Tranche,
+};
+use crate::domain::schedule::{DayCount, Payment};
+use chrono::NaiveDate;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Запрос v2: программа одним полем, срок до 30 лет, суммы - десятичные числа.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = v2::Request)]
+pub struct Request {
+    pub program: ProgramKind,
+    #[schema(value_type = String, example = "5000000.00")]
+    pub object_cost: Decimal,
+    #[schema(value_type = String, example = "1000000.00")]
+    pub initial_payment: Decimal,
+    pub months: u16,
+    // годовой облагаемый доход для расчета имущественного вычета
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub taxable_income: Option<Decimal>,
+    // прочие источники первоначального взноса и погашения
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributions: Vec<Contribution>,
+    // сведения о заемщике для проверки условий программы
+    #[serde(default)]
+    pub borrower: Borrower,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_borrowers: Vec<Borrower>,
+    // дополнительные опции, изменяющие ставку
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<RateOption>,
+    // комбинированный расчет: часть сверх лимита по другой программе
+    #[serde(default)]
+    pub combined: bool,
+    // выборка кредита траншами при строительстве
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disbursements: Vec<Disbursement>,
+    // дата выдачи, по умолчанию - текущая дата
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_date: Option<NaiveDate>,
+    // день месяца для платежей, по умолчанию - день выдачи
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_day: Option<u8>,
+    // конвенция начисления процентов
+    #[serde(default)]
+    pub day_count: DayCount,
+    // перенос дат платежей с нерабочих дней
+    #[serde(default)]
+    pub date_shift: DateShift,
+}
+
+/// Ответ v2: агрегаты расчета, суммы округлены до копеек.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = v2::Response)]
+pub struct Response {
+    pub id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<ProgramKind>,
+    pub months: u16,
+    #[schema(value_type = String, example = "10")]
+    pub rate: Decimal,
+    pub rate_breakdown: RateBreakdown,
+    #[schema(value_type = String, example = "4000000.00")]
+    pub loan_sum: Decimal,
+    #[schema(value_type = String, example = "38601.00")]
+    pub monthly_payment: Decimal,
+    #[schema(value_type = String, example = "5264240.00")]
+    pub overpayment: Decimal,
+    // полная стоимость кредита (сумма всех платежей)
+    #[schema(value_type = String, example = "9264240.00")]
+    pub total_cost: Decimal,
+    pub last_payment_date: String,
+    // проценты в период строительства
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub construction_interest: Option<Decimal>,
+    // доля платежа в совокупном доходе заемщиков, %
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub payment_to_income: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<Payment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tranches: Vec<Tranche>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deduction: Option<Deduction>,
+    // версия каталога программ, по которому выполнен расчет
+    pub catalog_version: String,
+}
+
+/// Десятичное число из суммы, округленное до копеек.
+fn money(amount: f64) -> Decimal {
+    let mut money: Decimal = Decimal::from_f64(amount).unwrap_or_default().round_dp(2);
+    money.rescale(2);
+    money
+}
+
+impl From<Request> for Mortgage {
+    fn from(req: Request) -> Self {
+        Self {
+            params: Params {
+                object_cost: req.object_cost.to_f64().unwrap_or_default(),
+                initial_payment: req.initial_payment.to_f64().unwrap_or_default(),
+                months: req.months,
+                taxable_income: req.taxable_income.and_then(|income| income.to_f64()),
+                contributions: req.contributions,
+                borrower: req.borrower,
+                co_borrowers: req.co_borrowers,
+                options: req.options,
+                combined: req.combined,
+                disbursements: req.disbursements,
+                issue_date: req.issue_date,
+                payment_day: req.payment_day,
+                day_count: req.day_count,
+                date_shift: req.date_shift,
+            },
+            program: Program::from(req.program),
+            ..Mortgage::default()
+        }
+    }
+}
+
+impl Response {
+    /// Конструктор.
+    pub fn new(id: u32, loan: Mortgage) -> Self {
+        let total_cost: f64 = loan.total_cost();
+        let aggregates: Aggregates = loan.aggregates;
+        Self {
+            id,
+            program: loan.program.kind(),
+            months: loan.params.months,
+            rate: Decimal::from_f64(aggregates.rate)
+                .unwrap_or_default()
+                .round_dp(4)
+                .normalize(),
+            rate_breakdown: aggregates.rate_breakdown,
+            loan_sum: money(aggregates.loan_sum),
+            monthly_payment: money(aggregates.monthly_payment),
+            overpayment: money(aggregates.overpayment),
+            total_cost: money(total_cost),
+            last_payment_date: aggregates.last_payment_date,
+            construction_interest: aggregates.construction_interest.map(money),
+            payment_to_income: aggregates.payment_to_income.map(money),
+            schedule: loan.schedule,
+            tranches: loan.tranches,
+            deduction: loan.deduction,
+            catalog_version: loan.catalog_version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::catalog::Catalog;
+    use std::str::FromStr;
+
+    fn request() -> Request {
+        serde_json::from_value(serde_json::json!({
+            "program": "base",
+            "object_cost": "4999999.50",
+            "initial_payment": 1_000_000,
+            "months": 300
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_response() {
+        let mut loan: Mortgage = Mortgage::from(request());
+        assert_eq!(loan.params.object_cost, 4_999_999.5);
+        assert_eq!(loan.program.base, Some(true));
+        assert!(loan.execute(&Catalog::default()).is_ok());
+        let actual: Response = Response::new(7, loan.clone());
+        assert_eq!(actual.id, 7);
+        assert_eq!(actual.program, Some(ProgramKind::Base));
+        assert_eq!(actual.months, 300);
+        assert_eq!(actual.loan_sum, Decimal::from_str("3999999.50").unwrap());
+        assert_eq!(
+            actual.monthly_payment,
+            money(loan.aggregates.monthly_payment)
+        );
+        // суммы сериализуются строками
+        let json: serde_json::Value = serde_json::to_value(&actual).unwrap();
+        assert_eq!(json["loan_sum"], "3999999.50");
+        assert_eq!(json["rate"], "10");
+        assert_eq!(json["catalog_version"], "builtin");
+    }
+}
@@ -1,42 +1,151 @@
 use crate::adapter::cache::{AppState, SharedState};
+#[cfg(unix)]
+use crate::adapter::reload::on_hangup;
+use crate::adapter::reload::{load, watch, LoadError};
 use crate::adapter::router::router;
-use crate::config::Cli;
+use crate::adapter::telemetry::{otlp, Exporter, OtlpLayer};
+use crate::config::{Config, ConfigError, LogConfig, LogFormat};
+use crate::domain::catalog::Catalog;
 use axum::Router;
-use clap::Parser;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::{env, io};
+use thiserror::Error;
+use tokio::net::TcpListener;
 use tokio::signal;
-use tracing::info;
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+/// Ошибки запуска сервиса.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    // некорректная конфигурация
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    // не загружен каталог программ или производственный календарь
+    #[error(transparent)]
+    Catalog(#[from] LoadError),
+    // не занят адрес
+    #[error("failed to bind {address}: {source}")]
+    Bind { address: String, source: io::Error },
+    // сервис завершился с ошибкой
+    #[error("server error: {0}")]
+    Serve(io::Error),
+}
 
 /// Основная функция. Инициализация и запуск сервиса.
-pub async fn execute() {
-    // cli-конфиг
-    let cfg: Cli = Cli::parse();
+pub async fn execute() -> Result<(), ServerError> {
+    // конфиг: умолчания, файл, переменные окружения, параметры запуска
+    let cfg: Config = Config::load()?;
+    // экспорт трассировки в коллектор OTLP
+    let (otlp, exporter): (Option<OtlpLayer>, Option<Exporter>) = match &cfg.telemetry.otlp_endpoint
+    {
+        Some(endpoint) => {
+            let (layer, exporter) = otlp(endpoint, &cfg.telemetry.service_name)
+                .map_err(|err| ConfigError::Invalid(format!("telemetry.otlp_endpoint: {err}")))?;
+            (Some(layer), Some(exporter))
+        }
+        None => (None, None),
+    };
     // включение трейсинга
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
+    logging(&cfg.log, otlp)?;
+    for name in Config::unknown_env(&env::vars().collect()) {
+        warn!("Unknown environment variable {name} is ignored");
+    }
+    if let Some(endpoint) = &cfg.telemetry.otlp_endpoint {
+        info!("Exporting traces to {endpoint}");
+    }
     // создание 'state' объекта
     let state: Arc<RwLock<AppState>> = SharedState::default();
+    {
+        let mut binding = state.write().unwrap();
+        binding.max_batch_size = cfg.cache.max_batch_size;
+        binding.max_cache_entries = cfg.cache.max_entries;
+        binding.http = cfg.http.clone();
+        binding.catalog_source = cfg.catalog.clone();
+        binding.admin_token = cfg.admin.token.clone();
+    }
+    // загрузка каталога программ и производственного календаря
+    let catalog: Catalog = load(&cfg.catalog)?;
+    info!(
+        "Loaded program catalog {} with {} programs and {} holidays",
+        catalog.version,
+        catalog.programs.len(),
+        catalog.calendar.holidays.len()
+    );
+    state.write().unwrap().catalog = Arc::new(catalog);
+    // перезагрузка каталога по SIGHUP и при изменении файлов
+    #[cfg(unix)]
+    tokio::spawn(on_hangup(Arc::clone(&state)));
+    if cfg.catalog.reload_interval_secs > 0
+        && (cfg.catalog.path.is_some() || cfg.catalog.holidays.is_some())
+    {
+        tokio::spawn(watch(
+            Arc::clone(&state),
+            Duration::from_secs(cfg.catalog.reload_interval_secs),
+        ));
+    }
     // хост и порт
-    let address: String = format!("{}:{}", cfg.host, cfg.port);
+    let address: String = format!("{}:{}", cfg.server.host, cfg.server.port);
     // создание роутера и регистрация хендлеров
-    let router: Router = router(state).await;
+    let router: Router = router(Arc::clone(&state)).await;
     // tcp-движок
-    let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+    let listener: TcpListener =
+        TcpListener::bind(&address)
+            .await
+            .map_err(|source| ServerError::Bind {
+                address: address.clone(),
+                source,
+            })?;
     info!(
         "🚀 Server started successfully. Listening on {}...",
-        listener.local_addr().unwrap()
+        listener.local_addr().map_err(ServerError::Serve)?
     );
     // запуск сервиса с graceful shutdown
     axum::serve(listener, router)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(shutdown_signal(
+            Arc::clone(&state),
+            Duration::from_secs(cfg.server.shutdown_delay_secs),
+        ))
         .await
-        .unwrap();
+        .map_err(ServerError::Serve)?;
+    // отправка оставшихся спанов
+    if let Some(exporter) = exporter {
+        exporter.flush().await;
+    }
+    Ok(())
+}
+
+/// Логирование в выбранном формате и экспорт спанов сервиса с фильтром уровня `log.level`:
+/// debug-спаны шагов расчета экспортируются только при уровне `debug`.
+fn logging(log: &LogConfig, otlp: Option<OtlpLayer>) -> Result<(), ConfigError> {
+    let filter = || {
+        EnvFilter::try_new(&log.level)
+            .map_err(|err| ConfigError::Invalid(format!("log.level {:?}: {err}", log.level)))
+    };
+    let fmt = fmt::layer().with_target(false);
+    let fmt: Box<dyn Layer<Registry> + Send + Sync> = match log.format {
+        LogFormat::Text => fmt.compact().boxed(),
+        LogFormat::Json => fmt
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> =
+        vec![fmt.with_filter(filter()?).boxed()];
+    if let Some(otlp) = otlp {
+        layers.push(otlp.with_filter(filter()?).boxed());
+    }
+    tracing_subscriber::registry().with(layers).init();
+    Ok(())
 }
 
-/// Graceful shutdown.
-async fn shutdown_signal() {
+/// Graceful shutdown: по сигналу сервис помечается неготовым (`/readyz`),
+/// после задержки перестает принимать соединения и дожидается текущих запросов.
+async fn shutdown_signal(state: SharedState, delay: Duration) {
     // сигнал "ctrl_c"
     let ctrl_c = async {
         signal::ctrl_c()
@@ -57,4 +166,7 @@ async fn shutdown_signal() {
         _ = ctrl_c => { info!("Shutting down server...") },
         _ = terminate => { info!("Shutting down server...") },
     }
+    // снятие с балансировки
+    state.write().unwrap().shutting_down = true;
+    tokio::time::sleep(delay).await;
 }